@@ -0,0 +1,851 @@
+//! Files loaded behind typed [`Handle`]s. Each file is loaded once, on a
+//! background thread, shared by every handle to it and freed when the
+//! last one is dropped. Assets reload in place when their files change.
+
+mod pool;
+mod watcher;
+
+use std::any::{Any, TypeId, type_name};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Weak};
+
+use crate::font::{BitmapFont, FontError, TrueTypeFont};
+use crate::image::{Image, ImageError};
+use crate::input::{InputError, InputMap};
+use crate::scene::{Scene, SceneError};
+use crate::sound::{Sound, SoundError};
+
+use pool::Pool;
+pub use watcher::AssetWatcher;
+
+/// Something [`Assets`] can load from, and reload from, a file.
+pub trait Asset: Sized + Send + 'static {
+    fn load(path: &Path) -> Result<Self, AssetError>;
+}
+
+impl Asset for Image {
+    fn load(path: &Path) -> Result<Self, AssetError> {
+        Ok(Image::load(path)?)
+    }
+}
+
+impl Asset for BitmapFont {
+    fn load(path: &Path) -> Result<Self, AssetError> {
+        Ok(BitmapFont::load_bmfont(path)?)
+    }
+}
+
+impl Asset for TrueTypeFont {
+    fn load(path: &Path) -> Result<Self, AssetError> {
+        Ok(TrueTypeFont::load(path)?)
+    }
+}
+
+impl Asset for Sound {
+    fn load(path: &Path) -> Result<Self, AssetError> {
+        Ok(Sound::load(path)?)
+    }
+}
+
+impl Asset for Scene {
+    fn load(path: &Path) -> Result<Self, AssetError> {
+        Ok(Scene::load(path)?)
+    }
+}
+
+impl Asset for InputMap {
+    fn load(path: &Path) -> Result<Self, AssetError> {
+        Ok(InputMap::load(path)?)
+    }
+}
+
+#[derive(Debug)]
+pub enum AssetError {
+    Image(ImageError),
+    Font(FontError),
+    Sound(SoundError),
+    Scene(SceneError),
+    Input(InputError),
+    Watch(notify::Error),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Image(err) => err.fmt(f),
+            Self::Font(err) => err.fmt(f),
+            Self::Sound(err) => err.fmt(f),
+            Self::Scene(err) => err.fmt(f),
+            Self::Input(err) => err.fmt(f),
+            Self::Watch(err) => write!(f, "failed to watch assets: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Image(err) => Some(err),
+            Self::Font(err) => Some(err),
+            Self::Sound(err) => Some(err),
+            Self::Scene(err) => Some(err),
+            Self::Input(err) => Some(err),
+            Self::Watch(err) => Some(err),
+        }
+    }
+}
+
+impl From<ImageError> for AssetError {
+    fn from(err: ImageError) -> Self {
+        Self::Image(err)
+    }
+}
+
+impl From<FontError> for AssetError {
+    fn from(err: FontError) -> Self {
+        Self::Font(err)
+    }
+}
+
+impl From<SoundError> for AssetError {
+    fn from(err: SoundError) -> Self {
+        Self::Sound(err)
+    }
+}
+
+impl From<SceneError> for AssetError {
+    fn from(err: SceneError) -> Self {
+        Self::Scene(err)
+    }
+}
+
+impl From<InputError> for AssetError {
+    fn from(err: InputError) -> Self {
+        Self::Input(err)
+    }
+}
+
+impl From<notify::Error> for AssetError {
+    fn from(err: notify::Error) -> Self {
+        Self::Watch(err)
+    }
+}
+
+/// Where an asset lives in its type's storage. The generation tells apart
+/// assets that reuse a freed slot.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct AssetId {
+    index: u32,
+    generation: u32,
+}
+
+/// Refers to a `T` in [`Assets`], before and after any reloads. The asset
+/// is freed once every clone of its handle has been dropped.
+pub struct Handle<T> {
+    id: Arc<AssetId>,
+    _asset: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> Handle<T> {
+    /// Identifies the asset without keeping it alive, e.g. to compare
+    /// with an [`AssetEvent`].
+    pub fn untyped(&self) -> UntypedHandle {
+        UntypedHandle {
+            type_id: TypeId::of::<T>(),
+            index: self.id.index,
+            generation: self.id.generation,
+        }
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: Arc::clone(&self.id),
+            _asset: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle<{}>({})", type_name::<T>(), self.id.index)
+    }
+}
+
+/// An asset of any type, which doesn't keep it loaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UntypedHandle {
+    type_id: TypeId,
+    index: u32,
+    generation: u32,
+}
+
+impl<T: 'static> PartialEq<Handle<T>> for UntypedHandle {
+    fn eq(&self, other: &Handle<T>) -> bool {
+        *self == other.untyped()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadState {
+    /// Queued or being read on a loader thread.
+    Loading,
+    Loaded,
+    /// Never loaded; see the [`AssetEvent::Failed`] for why.
+    Failed,
+}
+
+/// A finished load, or what happened to an asset whose file changed.
+#[derive(Debug)]
+pub enum AssetEvent {
+    Loaded {
+        handle: UntypedHandle,
+        path: PathBuf,
+    },
+    Reloaded {
+        handle: UntypedHandle,
+        path: PathBuf,
+    },
+    /// The file doesn't load; any previous version stays in use.
+    Failed {
+        handle: UntypedHandle,
+        path: PathBuf,
+        error: AssetError,
+    },
+}
+
+impl AssetEvent {
+    pub fn handle(&self) -> UntypedHandle {
+        match self {
+            Self::Loaded { handle, .. }
+            | Self::Reloaded { handle, .. }
+            | Self::Failed { handle, .. } => *handle,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Loaded { path, .. }
+            | Self::Reloaded { path, .. }
+            | Self::Failed { path, .. } => path,
+        }
+    }
+}
+
+/// A background load's result, sent back to [`Assets::update`].
+struct Finished {
+    type_id: TypeId,
+    index: u32,
+    generation: u32,
+    ticket: u32,
+    result: Result<Box<dyn Any + Send>, AssetError>,
+}
+
+struct Slot<T> {
+    /// `None` until the first load succeeds.
+    asset: Option<T>,
+    state: LoadState,
+    /// As given to [`Assets::load`], or `None` for added assets.
+    path: Option<PathBuf>,
+    /// `path` resolved, to match against changed files.
+    key: Option<PathBuf>,
+    version: u32,
+    /// Bumped per load so results of superseded loads are dropped.
+    ticket: u32,
+    handle: Weak<AssetId>,
+}
+
+impl<T: 'static> Slot<T> {
+    fn new(path: Option<&Path>, asset: Option<T>) -> Self {
+        Self {
+            state: match asset {
+                Some(_) => LoadState::Loaded,
+                None => LoadState::Loading,
+            },
+            asset,
+            path: path.map(Path::to_owned),
+            key: path.map(resolve),
+            version: 0,
+            ticket: 0,
+            handle: Weak::new(),
+        }
+    }
+
+    fn untyped(&self) -> Option<UntypedHandle> {
+        let id = self.handle.upgrade()?;
+        Some(UntypedHandle {
+            type_id: TypeId::of::<T>(),
+            index: id.index,
+            generation: id.generation,
+        })
+    }
+}
+
+struct Entry<T> {
+    generation: u32,
+    slot: Option<Slot<T>>,
+}
+
+/// Every asset of one type.
+struct Storage<T> {
+    entries: Vec<Entry<T>>,
+    /// Indices of freed entries, reused before growing.
+    free: Vec<u32>,
+    /// Entry of each loaded file.
+    by_key: HashMap<PathBuf, u32>,
+}
+
+impl<T: Asset> Storage<T> {
+    fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            free: Vec::new(),
+            by_key: HashMap::new(),
+        }
+    }
+
+    fn slot(&self, id: &AssetId) -> Option<&Slot<T>> {
+        let entry = self.entries.get(id.index as usize)?;
+        if entry.generation != id.generation {
+            return None;
+        }
+        entry.slot.as_ref()
+    }
+
+    fn slot_mut(
+        &mut self,
+        index: u32,
+        generation: u32,
+    ) -> Option<&mut Slot<T>> {
+        let entry = self.entries.get_mut(index as usize)?;
+        if entry.generation != generation {
+            return None;
+        }
+        entry.slot.as_mut()
+    }
+
+    /// Live handle to the asset loaded from `key`.
+    fn find(&self, key: &Path) -> Option<Handle<T>> {
+        let index = *self.by_key.get(key)?;
+        let slot = self.entries[index as usize]
+            .slot
+            .as_ref()?;
+        let id = slot.handle.upgrade()?;
+        Some(Handle { id, _asset: PhantomData })
+    }
+
+    fn push(&mut self, mut slot: Slot<T>) -> Handle<T> {
+        let index = self.free.pop().unwrap_or_else(|| {
+            self.entries
+                .push(Entry { generation: 0, slot: None });
+            self.entries.len() as u32 - 1
+        });
+        let entry = &mut self.entries[index as usize];
+        let id = Arc::new(AssetId { index, generation: entry.generation });
+        slot.handle = Arc::downgrade(&id);
+        if let Some(key) = &slot.key {
+            self.by_key.insert(key.clone(), index);
+        }
+        entry.slot = Some(slot);
+        Handle { id, _asset: PhantomData }
+    }
+}
+
+/// [`Storage`] without its asset type.
+trait AnyStorage: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn reload(&mut self, key: &Path, events: &mut Vec<AssetEvent>);
+    fn finish(&mut self, finished: Finished, events: &mut Vec<AssetEvent>);
+    /// Frees assets whose handles have all been dropped.
+    fn free_unused(&mut self);
+    fn keys(&self) -> Vec<&Path>;
+    fn len(&self) -> usize;
+}
+
+impl<T: Asset> AnyStorage for Storage<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn reload(&mut self, key: &Path, events: &mut Vec<AssetEvent>) {
+        let Some(&index) = self.by_key.get(key) else {
+            return;
+        };
+        let Some(slot) = &mut self.entries[index as usize].slot else {
+            return;
+        };
+        let (Some(handle), Some(path)) = (slot.untyped(), slot.path.clone())
+        else {
+            return;
+        };
+        slot.ticket += 1;
+        events.push(match T::load(&path) {
+            Ok(asset) => {
+                slot.asset = Some(asset);
+                slot.state = LoadState::Loaded;
+                slot.version += 1;
+                AssetEvent::Reloaded { handle, path }
+            }
+            Err(error) => {
+                if slot.asset.is_none() {
+                    slot.state = LoadState::Failed;
+                }
+                AssetEvent::Failed { handle, path, error }
+            }
+        });
+    }
+
+    fn finish(&mut self, finished: Finished, events: &mut Vec<AssetEvent>) {
+        let Some(slot) = self.slot_mut(finished.index, finished.generation)
+        else {
+            return;
+        };
+        if slot.ticket != finished.ticket {
+            return;
+        }
+        let (Some(handle), Some(path)) = (slot.untyped(), slot.path.clone())
+        else {
+            return;
+        };
+        events.push(match finished.result {
+            Ok(asset) => {
+                let asset = asset
+                    .downcast::<T>()
+                    .expect("results are routed by asset type");
+                slot.asset = Some(*asset);
+                slot.state = LoadState::Loaded;
+                AssetEvent::Loaded { handle, path }
+            }
+            Err(error) => {
+                slot.state = LoadState::Failed;
+                AssetEvent::Failed { handle, path, error }
+            }
+        });
+    }
+
+    fn free_unused(&mut self) {
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            let Some(slot) = entry
+                .slot
+                .take_if(|slot| slot.handle.strong_count() == 0)
+            else {
+                continue;
+            };
+            let index = index as u32;
+            if let Some(key) = slot.key
+                && self.by_key.get(&key) == Some(&index)
+            {
+                self.by_key.remove(&key);
+            }
+            entry.generation = entry.generation.wrapping_add(1);
+            self.free.push(index);
+        }
+    }
+
+    fn keys(&self) -> Vec<&Path> {
+        self.by_key
+            .keys()
+            .map(PathBuf::as_path)
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len() - self.free.len()
+    }
+}
+
+/// Every asset, by type.
+pub struct Assets {
+    storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+    /// Loader threads, started by the first [`load`](Self::load).
+    pool: Option<Pool>,
+    results: Sender<Finished>,
+    finished: Receiver<Finished>,
+}
+
+impl Default for Assets {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Assets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Assets")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Assets {
+    pub fn new() -> Self {
+        let (results, finished) = mpsc::channel();
+        Self {
+            storages: HashMap::new(),
+            pool: None,
+            results,
+            finished,
+        }
+    }
+
+    /// Starts loading `path` as a `T` on a loader thread, returning a
+    /// handle to it right away. Loading a file that's already loaded, or
+    /// loading, returns the same handle. Call [`update`] to receive the
+    /// result.
+    ///
+    /// [`update`]: Self::update
+    pub fn load<T: Asset>(&mut self, path: impl AsRef<Path>) -> Handle<T> {
+        let path = path.as_ref();
+        let storage = self.storage_mut::<T>();
+        let (handle, new) = match storage.find(&resolve(path)) {
+            Some(handle) => (handle, false),
+            None => (storage.push(Slot::new(Some(path), None)), true),
+        };
+        let slot = storage
+            .slot_mut(handle.id.index, handle.id.generation)
+            .expect("handles keep their slot alive");
+        // Failed loads are retried in case the file was fixed.
+        if new || slot.state == LoadState::Failed {
+            slot.state = LoadState::Loading;
+            slot.ticket += 1;
+            let ticket = slot.ticket;
+            self.queue_load::<T>(&handle.id, ticket, path.to_owned());
+        }
+        handle
+    }
+
+    /// Loads `path` as a `T` on this thread, unless it's already loaded.
+    pub fn load_blocking<T: Asset>(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<Handle<T>, AssetError> {
+        let path = path.as_ref();
+        if let Some(handle) = self.handle::<T>(path)
+            && self.load_state(&handle) == LoadState::Loaded
+        {
+            return Ok(handle);
+        }
+        let asset = T::load(path)?;
+        Ok(self.insert(path, asset))
+    }
+
+    /// Stores an asset the caller already loaded from `path`, replacing
+    /// any loaded before.
+    pub fn insert<T: Asset>(
+        &mut self,
+        path: impl AsRef<Path>,
+        asset: T,
+    ) -> Handle<T> {
+        let path = path.as_ref();
+        let storage = self.storage_mut::<T>();
+        let Some(handle) = storage.find(&resolve(path)) else {
+            return storage.push(Slot::new(Some(path), Some(asset)));
+        };
+        let slot = storage
+            .slot_mut(handle.id.index, handle.id.generation)
+            .expect("handles keep their slot alive");
+        if slot.asset.is_some() {
+            slot.version += 1;
+        }
+        slot.asset = Some(asset);
+        slot.state = LoadState::Loaded;
+        slot.ticket += 1;
+        handle
+    }
+
+    /// Stores an asset that has no file.
+    pub fn add<T: Asset>(&mut self, asset: T) -> Handle<T> {
+        self.storage_mut()
+            .push(Slot::new(None, Some(asset)))
+    }
+
+    /// The asset, or `None` while it's loading or if it failed to load.
+    pub fn get<T: Asset>(&self, handle: &Handle<T>) -> Option<&T> {
+        self.slot(handle)?.asset.as_ref()
+    }
+
+    /// Handle to the `T` loaded, or loading, from `path`.
+    pub fn handle<T: Asset>(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Option<Handle<T>> {
+        self.storage::<T>()?
+            .find(&resolve(path.as_ref()))
+    }
+
+    pub fn load_state<T: Asset>(&self, handle: &Handle<T>) -> LoadState {
+        self.slot(handle)
+            .map_or(LoadState::Failed, |slot| slot.state)
+    }
+
+    /// File `handle` was loaded from.
+    pub fn path<T: Asset>(&self, handle: &Handle<T>) -> Option<&Path> {
+        self.slot(handle)?.path.as_deref()
+    }
+
+    /// Number of times `handle`'s asset has been replaced since it first
+    /// loaded.
+    pub fn version<T: Asset>(&self, handle: &Handle<T>) -> u32 {
+        self.slot(handle)
+            .map_or(0, |slot| slot.version)
+    }
+
+    /// Resolved paths of every loaded file, for an [`AssetWatcher`].
+    pub fn files(&self) -> Vec<&Path> {
+        self.storages
+            .values()
+            .flat_map(|storage| storage.keys())
+            .collect()
+    }
+
+    /// Number of stored assets, including those still loading and those
+    /// whose handles were dropped since the last [`update`].
+    ///
+    /// [`update`]: Self::update
+    pub fn len(&self) -> usize {
+        self.storages
+            .values()
+            .map(|storage| storage.len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Stores finished background loads and frees assets that are no
+    /// longer referenced. Call once per frame.
+    pub fn update(&mut self) -> Vec<AssetEvent> {
+        let mut events = Vec::new();
+        while let Ok(finished) = self.finished.try_recv() {
+            if let Some(storage) = self.storages.get_mut(&finished.type_id) {
+                storage.finish(finished, &mut events);
+            }
+        }
+        for storage in self.storages.values_mut() {
+            storage.free_unused();
+        }
+        events
+    }
+
+    /// Reloads every asset loaded from `path`. Assets that fail to load
+    /// keep their previous version.
+    pub fn reload(&mut self, path: impl AsRef<Path>) -> Vec<AssetEvent> {
+        let key = resolve(path.as_ref());
+        let mut events = Vec::new();
+        for storage in self.storages.values_mut() {
+            storage.reload(&key, &mut events);
+        }
+        events
+    }
+
+    fn queue_load<T: Asset>(
+        &mut self,
+        id: &AssetId,
+        ticket: u32,
+        path: PathBuf,
+    ) {
+        let results = self.results.clone();
+        let (index, generation) = (id.index, id.generation);
+        self.pool
+            .get_or_insert_with(Pool::new)
+            .run(move || {
+                let result = T::load(&path)
+                    .map(|asset| Box::new(asset) as Box<dyn Any + Send>);
+                // Fails only if the assets were dropped meanwhile.
+                let _ = results.send(Finished {
+                    type_id: TypeId::of::<T>(),
+                    index,
+                    generation,
+                    ticket,
+                    result,
+                });
+            });
+    }
+
+    fn slot<T: Asset>(&self, handle: &Handle<T>) -> Option<&Slot<T>> {
+        self.storage::<T>()?.slot(&handle.id)
+    }
+
+    fn storage<T: Asset>(&self) -> Option<&Storage<T>> {
+        self.storages
+            .get(&TypeId::of::<T>())?
+            .as_any()
+            .downcast_ref()
+    }
+
+    fn storage_mut<T: Asset>(&mut self) -> &mut Storage<T> {
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Storage::<T>::new()))
+            .as_any_mut()
+            .downcast_mut()
+            .expect("storages are keyed by their asset type")
+    }
+}
+
+/// Absolute form of `path` so different spellings of one file match.
+/// Falls back to `path` itself while the file is missing, e.g. midway
+/// through an editor's save.
+fn resolve(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::color::Color;
+    use crate::framebuffer::Framebuffer;
+    use crate::ppm;
+
+    fn temp_image(name: &str, color: Color) -> PathBuf {
+        let path = env::temp_dir().join(name);
+        let mut framebuffer = Framebuffer::new(1, 1);
+        framebuffer.fill(color);
+        ppm::save(&path, &framebuffer).unwrap();
+        path
+    }
+
+    /// Updates `assets` until `handle` is done loading.
+    fn wait_for<T: Asset>(
+        assets: &mut Assets,
+        handle: &Handle<T>,
+    ) -> Vec<AssetEvent> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut events = Vec::new();
+        while assets.load_state(handle) == LoadState::Loading {
+            assert!(Instant::now() < deadline, "load timed out");
+            thread::sleep(Duration::from_millis(1));
+            events.extend(assets.update());
+        }
+        events
+    }
+
+    #[test]
+    fn loads_each_file_once_in_the_background() {
+        let path = temp_image("window_app_assets_async.ppm", Color::RED);
+        let mut assets = Assets::new();
+        let handle = assets.load::<Image>(&path);
+        let same = assets.load::<Image>(&path);
+        assert_eq!(handle, same);
+
+        let events = wait_for(&mut assets, &handle);
+
+        assert!(matches!(events[..], [AssetEvent::Loaded { .. }]));
+        assert_eq!(events[0].handle(), handle);
+        assert_eq!(assets.load_state(&handle), LoadState::Loaded);
+        assert_eq!(assets.get(&same).unwrap().pixel(0, 0), Some(Color::RED));
+        assert_eq!(assets.len(), 1);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn reports_failed_loads() {
+        let path = env::temp_dir().join("window_app_assets_missing.ppm");
+        let mut assets = Assets::new();
+        let handle = assets.load::<Image>(&path);
+
+        let events = wait_for(&mut assets, &handle);
+
+        assert!(matches!(events[..], [AssetEvent::Failed { .. }]));
+        assert_eq!(assets.load_state(&handle), LoadState::Failed);
+        assert!(assets.get(&handle).is_none());
+    }
+
+    #[test]
+    fn frees_assets_once_unreferenced() {
+        let path = temp_image("window_app_assets_free.ppm", Color::RED);
+        let mut assets = Assets::new();
+        let handle = assets
+            .load_blocking::<Image>(&path)
+            .unwrap();
+        let clone = handle.clone();
+        let added = assets.add(Image::new(2, 2));
+
+        drop(handle);
+        assets.update();
+        assert_eq!(assets.len(), 2);
+
+        drop(clone);
+        assets.update();
+        assert_eq!(assets.len(), 1);
+        assert!(assets.handle::<Image>(&path).is_none());
+        assert!(assets.files().is_empty());
+
+        // A freed slot is reused without reviving stale handles.
+        let reloaded = assets
+            .load_blocking::<Image>(&path)
+            .unwrap();
+        assert_eq!(assets.len(), 2);
+        assert_ne!(reloaded.untyped(), added.untyped());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn reloads_in_place_behind_the_same_handle() {
+        let path = temp_image("window_app_assets_reload.ppm", Color::RED);
+        let mut assets = Assets::new();
+        let handle = assets
+            .load_blocking::<Image>(&path)
+            .unwrap();
+        let unrelated = assets.add(Image::new(2, 2));
+
+        temp_image("window_app_assets_reload.ppm", Color::BLUE);
+        let events = assets.reload(&path);
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], AssetEvent::Reloaded { .. }));
+        assert_eq!(events[0].handle(), handle);
+        assert_eq!(assets.get(&handle).unwrap().pixel(0, 0), Some(Color::BLUE));
+        assert_eq!(assets.version(&handle), 1);
+        assert_eq!(assets.get(&unrelated).unwrap().width(), 2);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn failed_reloads_keep_the_old_asset() {
+        let path = temp_image("window_app_assets_broken.ppm", Color::RED);
+        let mut assets = Assets::new();
+        let handle = assets
+            .load_blocking::<Image>(&path)
+            .unwrap();
+
+        fs::write(&path, b"P6 broken").unwrap();
+        let events = assets.reload(&path);
+
+        assert!(matches!(events[..], [AssetEvent::Failed { .. }]));
+        assert_eq!(assets.get(&handle).unwrap().pixel(0, 0), Some(Color::RED));
+        assert_eq!(assets.version(&handle), 0);
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,50 @@
+use std::num::NonZeroUsize;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Most threads loading assets at once.
+const MAX_THREADS: usize = 4;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Threads that run queued jobs until the pool is dropped.
+pub(super) struct Pool {
+    jobs: Sender<Job>,
+}
+
+impl Pool {
+    pub(super) fn new() -> Self {
+        let threads = thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .min(MAX_THREADS);
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        for index in 0..threads {
+            let queue = Arc::clone(&queue);
+            thread::Builder::new()
+                .name(format!("asset-loader-{index}"))
+                .spawn(move || work(&queue))
+                .expect("failed to spawn asset loader thread");
+        }
+        Self { jobs }
+    }
+
+    pub(super) fn run(&self, job: impl FnOnce() + Send + 'static) {
+        // Workers only stop once `jobs` is dropped, so this can't fail.
+        let _ = self.jobs.send(Box::new(job));
+    }
+}
+
+fn work(queue: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .recv();
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::{AssetError, resolve};
+
+type Files = Arc<Mutex<HashSet<PathBuf>>>;
+
+/// Calls back with the path of each watched file that changes on disk.
+///
+/// Directories are watched rather than files so that editors which save
+/// by replacing the file are still noticed.
+pub struct AssetWatcher {
+    watcher: RecommendedWatcher,
+    files: Files,
+    /// Directories of the watched files.
+    dirs: HashSet<PathBuf>,
+}
+
+impl AssetWatcher {
+    /// `on_change` runs on the watcher's own thread, possibly several
+    /// times for a single save.
+    pub fn new(
+        on_change: impl Fn(PathBuf) + Send + 'static,
+    ) -> Result<Self, AssetError> {
+        let files = Files::default();
+        let watched = Arc::clone(&files);
+        let watcher = notify::recommended_watcher(
+            move |event: notify::Result<Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                if !is_change(&event.kind) {
+                    return;
+                }
+                let files = watched
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                for path in event.paths {
+                    let path = resolve(&path);
+                    if files.contains(&path) {
+                        on_change(path);
+                    }
+                }
+            },
+        )?;
+        Ok(Self { watcher, files, dirs: HashSet::new() })
+    }
+
+    /// Starts watching `path`; watching it twice has no further effect.
+    pub fn watch(&mut self, path: impl AsRef<Path>) -> Result<(), AssetError> {
+        let path = resolve(path.as_ref());
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_owned(),
+            _ => PathBuf::from("."),
+        };
+        let dir = fs::canonicalize(&dir).unwrap_or(dir);
+
+        if !self.dirs.contains(&dir) {
+            self.watcher
+                .watch(&dir, RecursiveMode::NonRecursive)?;
+            self.dirs.insert(dir);
+        }
+        self.files
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path);
+        Ok(())
+    }
+
+    /// Whether changes to `path` are reported.
+    pub fn is_watching(&self, path: impl AsRef<Path>) -> bool {
+        let files = self
+            .files
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        files.contains(&resolve(path.as_ref()))
+    }
+}
+
+fn is_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) => true,
+        EventKind::Modify(kind) => !matches!(kind, ModifyKind::Metadata(_)),
+        _ => false,
+    }
+}
@@ -0,0 +1,213 @@
+//! Image blitting onto [`Framebuffer`].
+
+use crate::color::Color;
+use crate::framebuffer::Framebuffer;
+use crate::image::Image;
+use crate::rect::Rect;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Filter {
+    #[default]
+    Nearest,
+    Bilinear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlitOptions {
+    /// Region of the image to draw; the whole image when `None`.
+    pub source: Option<Rect>,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// Multiplied into every sampled texel, including alpha.
+    pub tint: Color,
+    /// Extra opacity applied on top of the texel and tint alpha.
+    pub alpha: u8,
+    pub filter: Filter,
+}
+
+impl Default for BlitOptions {
+    fn default() -> Self {
+        Self {
+            source: None,
+            flip_x: false,
+            flip_y: false,
+            tint: Color::WHITE,
+            alpha: 255,
+            filter: Filter::Nearest,
+        }
+    }
+}
+
+impl Framebuffer {
+    /// Draws the whole image unscaled with its top-left corner at `(x, y)`.
+    pub fn draw_image(&mut self, image: &Image, x: i32, y: i32) {
+        let dest = Rect::new(x, y, image.width() as i32, image.height() as i32);
+        self.blit(image, dest, &BlitOptions::default());
+    }
+
+    /// Draws (part of) an image stretched to fill `dest`.
+    pub fn blit(&mut self, image: &Image, dest: Rect, options: &BlitOptions) {
+        let bounds =
+            Rect::new(0, 0, image.width() as i32, image.height() as i32);
+        let Some(source) = options
+            .source
+            .unwrap_or(bounds)
+            .intersect(&bounds)
+        else {
+            return;
+        };
+        let screen = Rect::new(0, 0, self.width() as i32, self.height() as i32);
+        let Some(visible) = dest.intersect(&screen) else {
+            return;
+        };
+
+        let scale_x = source.w as f64 / dest.w as f64;
+        let scale_y = source.h as f64 / dest.h as f64;
+
+        for y in visible.y..visible.bottom() {
+            let mut v = (y - dest.y) as f64 + 0.5;
+            if options.flip_y {
+                v = dest.h as f64 - v;
+            }
+            let v = source.y as f64 + v * scale_y;
+
+            for x in visible.x..visible.right() {
+                let mut u = (x - dest.x) as f64 + 0.5;
+                if options.flip_x {
+                    u = dest.w as f64 - u;
+                }
+                let u = source.x as f64 + u * scale_x;
+
+                let texel = match options.filter {
+                    Filter::Nearest => sample_nearest(image, &source, u, v),
+                    Filter::Bilinear => sample_bilinear(image, &source, u, v),
+                };
+                let color = modulate(texel, options.tint, options.alpha);
+                if color.a != 0 {
+                    self.blend_pixel(x as usize, y as usize, color);
+                }
+            }
+        }
+    }
+}
+
+fn texel(image: &Image, source: &Rect, x: i32, y: i32) -> Color {
+    let x = x.clamp(source.x, source.right() - 1) as usize;
+    let y = y.clamp(source.y, source.bottom() - 1) as usize;
+    image.pixels()[y * image.width() + x]
+}
+
+fn sample_nearest(image: &Image, source: &Rect, u: f64, v: f64) -> Color {
+    texel(image, source, u.floor() as i32, v.floor() as i32)
+}
+
+/// Interpolates the four texels around `(u, v)`, clamped to `source`.
+fn sample_bilinear(image: &Image, source: &Rect, u: f64, v: f64) -> Color {
+    let (u, v) = (u - 0.5, v - 0.5);
+    let (x0, y0) = (u.floor() as i32, v.floor() as i32);
+    let (fx, fy) = (u - u.floor(), v - v.floor());
+
+    let corners = [
+        (texel(image, source, x0, y0), (1.0 - fx) * (1.0 - fy)),
+        (texel(image, source, x0 + 1, y0), fx * (1.0 - fy)),
+        (texel(image, source, x0, y0 + 1), (1.0 - fx) * fy),
+        (texel(image, source, x0 + 1, y0 + 1), fx * fy),
+    ];
+
+    // Weight color by alpha so transparent texels don't bleed their RGB.
+    let (mut r, mut g, mut b, mut a) = (0.0, 0.0, 0.0, 0.0);
+    for (color, weight) in corners {
+        let alpha = color.a as f64 * weight;
+        r += color.r as f64 * alpha;
+        g += color.g as f64 * alpha;
+        b += color.b as f64 * alpha;
+        a += alpha;
+    }
+    if a == 0.0 {
+        return Color::TRANSPARENT;
+    }
+
+    let channel = |c: f64| (c / a).round().clamp(0.0, 255.0) as u8;
+    Color::rgba(channel(r), channel(g), channel(b), a.round() as u8)
+}
+
+fn modulate(color: Color, tint: Color, alpha: u8) -> Color {
+    let mul = |a: u8, b: u8| ((a as u32 * b as u32 + 127) / 255) as u8;
+    Color::rgba(
+        mul(color.r, tint.r),
+        mul(color.g, tint.g),
+        mul(color.b, tint.b),
+        mul(mul(color.a, tint.a), alpha),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> Image {
+        Image::from_pixels(
+            2,
+            2,
+            vec![Color::RED, Color::GREEN, Color::BLUE, Color::WHITE],
+        )
+    }
+
+    #[test]
+    fn draw_image_copies_pixels_and_clips() {
+        let mut fb = Framebuffer::new(3, 3);
+        fb.draw_image(&checker(), 1, 1);
+        fb.draw_image(&checker(), -1, -1);
+
+        assert_eq!(fb.pixel(0, 0), Some(Color::WHITE.to_0rgb()));
+        assert_eq!(fb.pixel(2, 1), Some(Color::GREEN.to_0rgb()));
+        assert_eq!(fb.pixel(2, 2), Some(Color::WHITE.to_0rgb()));
+    }
+
+    #[test]
+    fn nearest_scaling_with_flip_and_source_rect() {
+        let mut fb = Framebuffer::new(4, 2);
+        let options = BlitOptions {
+            source: Some(Rect::new(0, 0, 2, 1)),
+            flip_x: true,
+            ..BlitOptions::default()
+        };
+        fb.blit(&checker(), Rect::new(0, 0, 4, 2), &options);
+
+        let row: Vec<_> = (0..4)
+            .map(|x| fb.pixel(x, 1).unwrap())
+            .collect();
+        let (red, green) = (Color::RED.to_0rgb(), Color::GREEN.to_0rgb());
+        assert_eq!(row, [green, green, red, red]);
+    }
+
+    #[test]
+    fn tint_and_alpha_modulate_texels() {
+        let mut fb = Framebuffer::new(1, 1);
+        let image = Image::from_pixels(1, 1, vec![Color::WHITE]);
+        let options = BlitOptions {
+            tint: Color::rgb(255, 0, 0),
+            alpha: 128,
+            ..BlitOptions::default()
+        };
+        fb.blit(&image, Rect::new(0, 0, 1, 1), &options);
+
+        assert_eq!(fb.pixel(0, 0), Some(0x00800000));
+    }
+
+    #[test]
+    fn bilinear_blends_neighbours() {
+        let image = Image::from_pixels(2, 1, vec![Color::BLACK, Color::WHITE]);
+        let mut fb = Framebuffer::new(4, 1);
+        let options = BlitOptions {
+            filter: Filter::Bilinear,
+            ..BlitOptions::default()
+        };
+        fb.blit(&image, Rect::new(0, 0, 4, 1), &options);
+
+        let row: Vec<_> = (0..4)
+            .map(|x| fb.pixel(x, 0).unwrap() & 0xFF)
+            .collect();
+        assert_eq!(row, [0x00, 0x40, 0xBF, 0xFF]);
+    }
+}
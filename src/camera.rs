@@ -0,0 +1,138 @@
+//! World-to-screen mapping.
+
+use crate::collision::Aabb;
+use crate::math::Vec2;
+
+/// View onto the world, stored as a resource.
+///
+/// World units are independent of the window: the camera centers
+/// `position` in a viewport of `viewport` pixels, scaled by `zoom` and
+/// turned by `rotation`. Resizing the window only changes the viewport.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera2D {
+    /// World point shown at the center of the viewport.
+    pub position: Vec2,
+    /// Screen pixels per world unit.
+    pub zoom: f64,
+    /// Clockwise rotation of the world on screen, in radians.
+    pub rotation: f64,
+    /// Size of the screen area drawn to, in pixels.
+    pub viewport: Vec2,
+}
+
+impl Camera2D {
+    /// Unzoomed camera over a `width` x `height` viewport, showing world
+    /// coordinates equal to pixel coordinates.
+    pub fn new(width: f64, height: f64) -> Self {
+        let viewport = Vec2::new(width, height);
+        Self {
+            position: viewport / 2.0,
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport,
+        }
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_zoom(mut self, zoom: f64) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn world_to_screen(&self, point: Vec2) -> Vec2 {
+        (point - self.position).rotate(self.rotation) * self.zoom
+            + self.viewport / 2.0
+    }
+
+    /// Inverse of [`world_to_screen`], e.g. for mouse clicks.
+    ///
+    /// [`world_to_screen`]: Camera2D::world_to_screen
+    pub fn screen_to_world(&self, point: Vec2) -> Vec2 {
+        ((point - self.viewport / 2.0) / self.zoom).rotate(-self.rotation)
+            + self.position
+    }
+
+    /// Smallest world-space box containing everything on screen.
+    pub fn visible_bounds(&self) -> Aabb {
+        let corners = [
+            Vec2::ZERO,
+            Vec2::new(self.viewport.x, 0.0),
+            Vec2::new(0.0, self.viewport.y),
+            self.viewport,
+        ]
+        .map(|corner| self.screen_to_world(corner));
+        let first = Aabb::new(corners[0], corners[0]);
+        corners[1..]
+            .iter()
+            .fold(first, |bounds, &corner| {
+                bounds.union(&Aabb::new(corner, corner))
+            })
+    }
+
+    /// Centers `area` and zooms so all of it fits in the viewport.
+    pub fn fit(&mut self, area: Aabb) {
+        let size = area.half_extents() * 2.0;
+        self.position = area.center();
+        self.zoom = (self.viewport.x / size.x).min(self.viewport.y / size.y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Vec2, b: Vec2) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    #[test]
+    fn screen_and_world_round_trip() {
+        let camera = Camera2D::new(200.0, 100.0)
+            .with_position(Vec2::new(500.0, -40.0))
+            .with_zoom(2.5)
+            .with_rotation(0.7);
+
+        for point in [Vec2::ZERO, Vec2::new(13.0, 87.0), Vec2::new(200.0, 0.0)]
+        {
+            let world = camera.screen_to_world(point);
+            assert!(close(camera.world_to_screen(world), point));
+        }
+        assert!(close(
+            camera.world_to_screen(Vec2::new(500.0, -40.0)),
+            Vec2::new(100.0, 50.0)
+        ));
+    }
+
+    #[test]
+    fn zoom_scales_about_the_center() {
+        let camera = Camera2D::new(200.0, 100.0).with_zoom(2.0);
+
+        assert_eq!(
+            camera.world_to_screen(Vec2::new(110.0, 50.0)),
+            Vec2::new(120.0, 50.0)
+        );
+        let visible = camera.visible_bounds();
+        assert_eq!(visible.min, Vec2::new(50.0, 25.0));
+        assert_eq!(visible.max, Vec2::new(150.0, 75.0));
+    }
+
+    #[test]
+    fn fit_shows_the_whole_area() {
+        let mut camera = Camera2D::new(200.0, 100.0);
+        camera.fit(Aabb::new(Vec2::ZERO, Vec2::new(1000.0, 1000.0)));
+
+        let visible = camera.visible_bounds();
+        assert_eq!(camera.zoom, 0.1);
+        assert_eq!((visible.min.y, visible.max.y), (0.0, 1000.0));
+        assert!(visible.min.x < 0.0 && visible.max.x > 1000.0);
+    }
+}
@@ -0,0 +1,179 @@
+//! Fixed logical resolution scaled up to whatever size the window is.
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::Color;
+use crate::framebuffer::Framebuffer;
+use crate::rect::Rect;
+
+const LETTERBOX_COLOR: Color = Color::BLACK;
+
+/// How a [`VirtualCanvas`] is scaled to fill the window.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub enum ScaleMode {
+    /// Largest whole multiple that fits, so every logical pixel is the
+    /// same size. Falls back to [`Fit`] in windows smaller than the
+    /// canvas.
+    ///
+    /// [`Fit`]: ScaleMode::Fit
+    #[default]
+    Integer,
+    /// Largest size that fits while keeping the aspect ratio, with bars
+    /// filling the rest.
+    Fit,
+    /// Fills the whole window, distorting the aspect ratio.
+    Stretch,
+}
+
+/// Logical resolution the game renders at, independent of the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VirtualCanvas {
+    pub width: usize,
+    pub height: usize,
+    pub mode: ScaleMode,
+}
+
+impl VirtualCanvas {
+    pub const fn new(width: usize, height: usize, mode: ScaleMode) -> Self {
+        Self { width, height, mode }
+    }
+
+    /// Where the canvas lands in a `window_width` x `window_height`
+    /// window.
+    pub fn viewport(&self, window_width: usize, window_height: usize) -> Rect {
+        let (canvas_w, canvas_h) = (self.width.max(1), self.height.max(1));
+        let (w, h) = match self.mode {
+            ScaleMode::Stretch => (window_width, window_height),
+            ScaleMode::Integer
+                if window_width >= canvas_w && window_height >= canvas_h =>
+            {
+                let scale =
+                    (window_width / canvas_w).min(window_height / canvas_h);
+                (canvas_w * scale, canvas_h * scale)
+            }
+            ScaleMode::Integer | ScaleMode::Fit => {
+                let scale = (window_width as f64 / canvas_w as f64)
+                    .min(window_height as f64 / canvas_h as f64);
+                (
+                    ((canvas_w as f64 * scale).round() as usize).max(1),
+                    ((canvas_h as f64 * scale).round() as usize).max(1),
+                )
+            }
+        };
+        Rect::new(
+            ((window_width - w.min(window_width)) / 2) as i32,
+            ((window_height - h.min(window_height)) / 2) as i32,
+            w as i32,
+            h as i32,
+        )
+    }
+
+    /// Canvas coordinates of a point in window pixels. Points over the
+    /// bars map outside the canvas.
+    pub fn window_to_canvas(
+        &self,
+        (window_width, window_height): (usize, usize),
+        (x, y): (f64, f64),
+    ) -> (f64, f64) {
+        let viewport = self.viewport(window_width, window_height);
+        (
+            (x - viewport.x as f64) * self.width as f64 / viewport.w as f64,
+            (y - viewport.y as f64) * self.height as f64 / viewport.h as f64,
+        )
+    }
+
+    /// Scales `canvas` into all of `target` with nearest-neighbour
+    /// sampling, filling the bars around it.
+    pub fn present(&self, canvas: &Framebuffer, target: &mut Framebuffer) {
+        let viewport = self.viewport(target.width(), target.height());
+        target.fill(LETTERBOX_COLOR);
+        if canvas.width() == 0 || canvas.height() == 0 {
+            return;
+        }
+
+        let (src_w, src_h) = (canvas.width(), canvas.height());
+        let columns: Vec<usize> = (0..viewport.w as usize)
+            .map(|x| x * src_w / viewport.w as usize)
+            .collect();
+        let target_w = target.width();
+        for y in 0..viewport.h as usize {
+            let src_y = y * src_h / viewport.h as usize;
+            let src_row = &canvas.pixels()[src_y * src_w..][..src_w];
+            let start =
+                (viewport.y as usize + y) * target_w + viewport.x as usize;
+            let dst_row = &mut target.pixels_mut()[start..][..columns.len()];
+            for (dst, &src_x) in dst_row.iter_mut().zip(&columns) {
+                *dst = src_row[src_x];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANVAS: (usize, usize) = (320, 180);
+
+    fn canvas(mode: ScaleMode) -> VirtualCanvas {
+        VirtualCanvas::new(CANVAS.0, CANVAS.1, mode)
+    }
+
+    #[test]
+    fn viewports_per_mode() {
+        let integer = canvas(ScaleMode::Integer);
+        assert_eq!(integer.viewport(1000, 600), Rect::new(20, 30, 960, 540));
+        assert_eq!(integer.viewport(200, 200), Rect::new(0, 43, 200, 113));
+
+        let fit = canvas(ScaleMode::Fit);
+        assert_eq!(fit.viewport(1000, 600), Rect::new(0, 18, 1000, 563));
+
+        let stretch = canvas(ScaleMode::Stretch);
+        assert_eq!(stretch.viewport(1000, 600), Rect::new(0, 0, 1000, 600));
+    }
+
+    #[test]
+    fn cursor_maps_back_through_the_bars() {
+        let canvas = canvas(ScaleMode::Integer);
+
+        assert_eq!(
+            canvas.window_to_canvas((1000, 600), (20.0, 30.0)),
+            (0.0, 0.0)
+        );
+        assert_eq!(
+            canvas.window_to_canvas((1000, 600), (500.0, 300.0)),
+            (160.0, 90.0)
+        );
+        assert_eq!(
+            canvas.window_to_canvas((1000, 600), (2.0, 0.0)),
+            (-6.0, -10.0)
+        );
+    }
+
+    #[test]
+    fn present_upscales_with_bars() {
+        let mut small = Framebuffer::new(2, 1);
+        small.set_pixel(0, 0, Color::RED.to_0rgb());
+        small.set_pixel(1, 0, Color::BLUE.to_0rgb());
+        let mut window = Framebuffer::new(5, 4);
+
+        VirtualCanvas::new(2, 1, ScaleMode::Integer)
+            .present(&small, &mut window);
+
+        let (r, b, k) = (
+            Color::RED.to_0rgb(),
+            Color::BLUE.to_0rgb(),
+            Color::BLACK.to_0rgb(),
+        );
+        #[rustfmt::skip]
+        let expected = [
+            k, k, k, k, k,
+            r, r, b, b, k,
+            r, r, b, b, k,
+            k, k, k, k, k,
+        ];
+        assert_eq!(window.pixels(), expected);
+    }
+}
@@ -0,0 +1,516 @@
+//! Collision shapes, overlap tests and contact manifolds, plus a
+//! [`SpatialHash`] broadphase for finding candidate pairs.
+//!
+//! Touching shapes count as colliding, with zero depth.
+
+mod spatial_hash;
+
+use serde::{Deserialize, Serialize};
+
+use crate::math::Vec2;
+
+pub use spatial_hash::SpatialHash;
+
+/// Favour the first shape's faces when two axes separate about equally,
+/// so the reference face doesn't flip between ticks.
+const RELATIVE_TOLERANCE: f64 = 0.95;
+const ABSOLUTE_TOLERANCE: f64 = 0.01;
+
+/// Axis-aligned box.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center(center: Vec2, half_extents: Vec2) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec2 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.min(other.min), self.max.max(other.max))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f64,
+}
+
+impl Circle {
+    pub fn new(center: Vec2, radius: f64) -> Self {
+        Self { center, radius }
+    }
+}
+
+/// Oriented box, rotated `rotation` radians about its center.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Obb {
+    pub center: Vec2,
+    pub half_extents: Vec2,
+    pub rotation: f64,
+}
+
+impl Obb {
+    pub fn new(center: Vec2, half_extents: Vec2, rotation: f64) -> Self {
+        Self { center, half_extents, rotation }
+    }
+
+    /// The box's local x and y axes in world space.
+    pub fn axes(&self) -> [Vec2; 2] {
+        [Vec2::X.rotate(self.rotation), Vec2::Y.rotate(self.rotation)]
+    }
+
+    pub fn corners(&self) -> [Vec2; 4] {
+        let [x, y] = self.axes();
+        let (hx, hy) = (x * self.half_extents.x, y * self.half_extents.y);
+        let c = self.center;
+        [c - hx - hy, c + hx - hy, c + hx + hy, c - hx + hy]
+    }
+}
+
+impl From<Aabb> for Obb {
+    fn from(aabb: Aabb) -> Self {
+        Self::new(aabb.center(), aabb.half_extents(), 0.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    Aabb(Aabb),
+    Circle(Circle),
+    Obb(Obb),
+}
+
+impl Shape {
+    /// Smallest axis-aligned box containing the shape.
+    pub fn bounds(&self) -> Aabb {
+        match self {
+            Self::Aabb(aabb) => *aabb,
+            Self::Circle(circle) => Aabb::from_center(
+                circle.center,
+                Vec2::new(circle.radius, circle.radius),
+            ),
+            Self::Obb(obb) => {
+                let [x, y] = obb.axes();
+                let extent =
+                    x.abs() * obb.half_extents.x + y.abs() * obb.half_extents.y;
+                Aabb::from_center(obb.center, extent)
+            }
+        }
+    }
+
+    pub fn translated(&self, offset: Vec2) -> Shape {
+        match *self {
+            Self::Aabb(aabb) => {
+                Self::Aabb(Aabb::new(aabb.min + offset, aabb.max + offset))
+            }
+            Self::Circle(circle) => Self::Circle(Circle {
+                center: circle.center + offset,
+                ..circle
+            }),
+            Self::Obb(obb) => {
+                Self::Obb(Obb { center: obb.center + offset, ..obb })
+            }
+        }
+    }
+
+    /// Rotated by `rotation` radians about the origin, then translated.
+    pub fn transformed(&self, offset: Vec2, rotation: f64) -> Shape {
+        if rotation == 0.0 {
+            return self.translated(offset);
+        }
+        match *self {
+            Self::Circle(circle) => Self::Circle(Circle {
+                center: circle.center.rotate(rotation) + offset,
+                ..circle
+            }),
+            _ => {
+                let obb = self
+                    .as_obb()
+                    .expect("only circles lack a box");
+                Self::Obb(Obb {
+                    center: obb.center.rotate(rotation) + offset,
+                    rotation: obb.rotation + rotation,
+                    ..obb
+                })
+            }
+        }
+    }
+
+    /// Moment of inertia about the origin for a uniform `mass`.
+    pub fn inertia(&self, mass: f64) -> f64 {
+        let (center, about_center) = match *self {
+            Self::Circle(circle) => {
+                (circle.center, mass * circle.radius * circle.radius / 2.0)
+            }
+            _ => {
+                let obb = self
+                    .as_obb()
+                    .expect("only circles lack a box");
+                let size = obb.half_extents * 2.0;
+                (obb.center, mass * size.length_squared() / 12.0)
+            }
+        };
+        about_center + mass * center.length_squared()
+    }
+
+    /// The shape as an oriented box, unless it's a circle.
+    pub fn as_obb(&self) -> Option<Obb> {
+        match *self {
+            Self::Aabb(aabb) => Some(aabb.into()),
+            Self::Obb(obb) => Some(obb),
+            Self::Circle(_) => None,
+        }
+    }
+}
+
+/// How two colliding shapes touch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Manifold {
+    /// Unit vector from the first shape towards the second.
+    pub normal: Vec2,
+    /// Distance to move the shapes apart along `normal` to separate them.
+    pub depth: f64,
+    points: [Vec2; 2],
+    point_count: usize,
+}
+
+impl Manifold {
+    fn new(normal: Vec2, depth: f64, points: &[Vec2]) -> Self {
+        let mut manifold = Self {
+            normal,
+            depth,
+            points: [Vec2::ZERO; 2],
+            point_count: points.len().min(2),
+        };
+        manifold.points[..manifold.point_count]
+            .copy_from_slice(&points[..manifold.point_count]);
+        manifold
+    }
+
+    /// World-space contact points, one or two.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points[..self.point_count]
+    }
+
+    /// The same contact seen from the other shape.
+    pub fn flipped(self) -> Self {
+        Self { normal: -self.normal, ..self }
+    }
+}
+
+pub fn overlaps(a: &Shape, b: &Shape) -> bool {
+    match (a, b) {
+        (Shape::Aabb(a), Shape::Aabb(b)) => a.overlaps(b),
+        _ => collide(a, b).is_some(),
+    }
+}
+
+/// Contact between `a` and `b`, or `None` if they are apart.
+pub fn collide(a: &Shape, b: &Shape) -> Option<Manifold> {
+    match (a, b) {
+        (Shape::Circle(a), Shape::Circle(b)) => circle_circle(a, b),
+        (Shape::Circle(circle), other) => circle_box(circle, &other.as_obb()?),
+        (other, Shape::Circle(circle)) => {
+            circle_box(circle, &other.as_obb()?).map(Manifold::flipped)
+        }
+        _ => box_box(&a.as_obb()?, &b.as_obb()?),
+    }
+}
+
+fn circle_circle(a: &Circle, b: &Circle) -> Option<Manifold> {
+    let delta = b.center - a.center;
+    let distance = delta.length();
+    let radii = a.radius + b.radius;
+    if distance > radii {
+        return None;
+    }
+
+    let normal = if distance > 0.0 { delta / distance } else { Vec2::X };
+    let depth = radii - distance;
+    let point = a.center + normal * (a.radius - depth / 2.0);
+    Some(Manifold::new(normal, depth, &[point]))
+}
+
+/// Contact with the normal pointing from the circle into the box.
+fn circle_box(circle: &Circle, obb: &Obb) -> Option<Manifold> {
+    let local = (circle.center - obb.center).rotate(-obb.rotation);
+    let h = obb.half_extents;
+    let closest = local.clamp(-h, h);
+
+    // `outward` points from the box towards the circle, in box space.
+    let (outward, depth, point) = if closest == local {
+        let (gap_x, gap_y) = (h.x - local.x.abs(), h.y - local.y.abs());
+        if gap_x < gap_y {
+            let side = local.x.signum();
+            (
+                Vec2::new(side, 0.0),
+                gap_x + circle.radius,
+                Vec2::new(side * h.x, local.y),
+            )
+        } else {
+            let side = local.y.signum();
+            (
+                Vec2::new(0.0, side),
+                gap_y + circle.radius,
+                Vec2::new(local.x, side * h.y),
+            )
+        }
+    } else {
+        let delta = local - closest;
+        let distance = delta.length();
+        if distance > circle.radius {
+            return None;
+        }
+        (delta / distance, circle.radius - distance, closest)
+    };
+
+    let normal = -outward.rotate(obb.rotation);
+    let point = obb.center + point.rotate(obb.rotation);
+    Some(Manifold::new(normal, depth, &[point]))
+}
+
+/// Separating-axis test over both boxes' axes, with the incident face
+/// clipped against the reference face for up to two contact points.
+fn box_box(a: &Obb, b: &Obb) -> Option<Manifold> {
+    let boxes = [a, b];
+    let axes = [a.axes(), b.axes()];
+    let delta = b.center - a.center;
+
+    // (separation, normal from a to b, owner of the reference face, axis)
+    let mut best: Option<(f64, Vec2, usize, usize)> = None;
+    for owner in 0..2 {
+        let other = 1 - owner;
+        for axis_index in 0..2 {
+            let axis = axes[owner][axis_index];
+            let own_radius = component(boxes[owner].half_extents, axis_index);
+            let other_radius =
+                projected_radius(boxes[other], &axes[other], axis);
+            let distance = delta.dot(axis);
+            let separation = distance.abs() - own_radius - other_radius;
+            if separation > 0.0 {
+                return None;
+            }
+
+            let normal = if distance < 0.0 { -axis } else { axis };
+            let better = best.is_none_or(|(best_sep, ..)| {
+                separation > best_sep * RELATIVE_TOLERANCE + ABSOLUTE_TOLERANCE
+            });
+            if better {
+                best = Some((separation, normal, owner, axis_index));
+            }
+        }
+    }
+    let (separation, normal, owner, axis_index) = best?;
+
+    let reference = boxes[owner];
+    let incident = boxes[1 - owner];
+    // Reference face normal, pointing at the incident box.
+    let face_normal = if owner == 0 { normal } else { -normal };
+    let face_center = reference.center
+        + face_normal * component(reference.half_extents, axis_index);
+    let tangent = face_normal.perp();
+    let half_length = component(reference.half_extents, 1 - axis_index);
+
+    let incident_face = incident_face(incident, face_normal);
+    let offset = tangent.dot(face_center);
+    let clipped = clip(incident_face, tangent, offset + half_length)
+        .and_then(|segment| clip(segment, -tangent, half_length - offset));
+
+    let mut points = Vec::with_capacity(2);
+    if let Some(segment) = clipped {
+        for point in segment {
+            if face_normal.dot(point - face_center) <= 0.0 {
+                points.push(point);
+            }
+        }
+    }
+    if points.is_empty() {
+        let deepest = incident_face
+            .into_iter()
+            .min_by(|p, q| {
+                face_normal
+                    .dot(*p)
+                    .total_cmp(&face_normal.dot(*q))
+            })
+            .expect("a face has two vertices");
+        points.push(deepest);
+    }
+
+    Some(Manifold::new(normal, -separation, &points))
+}
+
+fn component(v: Vec2, index: usize) -> f64 {
+    if index == 0 { v.x } else { v.y }
+}
+
+/// Half the length of `obb` projected onto `axis`.
+fn projected_radius(obb: &Obb, axes: &[Vec2; 2], axis: Vec2) -> f64 {
+    obb.half_extents.x * axes[0].dot(axis).abs()
+        + obb.half_extents.y * axes[1].dot(axis).abs()
+}
+
+/// The edge of `obb` whose outward normal most opposes `normal`.
+fn incident_face(obb: &Obb, normal: Vec2) -> [Vec2; 2] {
+    let [x, y] = obb.axes();
+    let h = obb.half_extents;
+    let candidates = [(x, h.x, y, h.y), (y, h.y, x, h.x)];
+
+    let (axis, extent, tangent, half_length) = candidates
+        .into_iter()
+        .max_by(|p, q| {
+            p.0.dot(normal)
+                .abs()
+                .total_cmp(&q.0.dot(normal).abs())
+        })
+        .expect("two candidates");
+    let outward = if axis.dot(normal) > 0.0 { -axis } else { axis };
+    let center = obb.center + outward * extent;
+    [center - tangent * half_length, center + tangent * half_length]
+}
+
+/// Keeps the part of the segment where `normal.dot(p) <= offset`.
+fn clip(segment: [Vec2; 2], normal: Vec2, offset: f64) -> Option<[Vec2; 2]> {
+    let [a, b] = segment;
+    let (da, db) = (normal.dot(a) - offset, normal.dot(b) - offset);
+    match (da <= 0.0, db <= 0.0) {
+        (true, true) => Some(segment),
+        (false, false) => None,
+        _ => {
+            let crossing = a + (b - a) * (da / (da - db));
+            Some(if da <= 0.0 { [a, crossing] } else { [crossing, b] })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_4;
+
+    use super::*;
+
+    fn close(a: Vec2, b: Vec2) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    fn square(x: f64, y: f64, size: f64) -> Shape {
+        Shape::Aabb(Aabb::new(Vec2::new(x, y), Vec2::new(x + size, y + size)))
+    }
+
+    #[test]
+    fn circles_push_apart_along_centers() {
+        let a = Shape::Circle(Circle::new(Vec2::ZERO, 2.0));
+        let b = Shape::Circle(Circle::new(Vec2::new(3.0, 0.0), 2.0));
+
+        let contact = collide(&a, &b).unwrap();
+
+        assert_eq!(contact.normal, Vec2::X);
+        assert_eq!(contact.depth, 1.0);
+        assert_eq!(contact.points(), [Vec2::new(1.5, 0.0)]);
+        assert!(!overlaps(&a, &b.translated(Vec2::new(1.5, 0.0))));
+    }
+
+    #[test]
+    fn boxes_use_the_shallowest_axis_with_two_points() {
+        let a = square(0.0, 0.0, 10.0);
+        let b = square(8.0, 2.0, 10.0);
+
+        let contact = collide(&a, &b).unwrap();
+
+        assert_eq!(contact.normal, Vec2::X);
+        assert_eq!(contact.depth, 2.0);
+        assert_eq!(contact.points().len(), 2);
+        assert!(
+            contact
+                .points()
+                .iter()
+                .all(|p| p.x == 8.0)
+        );
+        assert_eq!(collide(&b, &a).unwrap().normal, -Vec2::X);
+    }
+
+    #[test]
+    fn touching_counts_but_gaps_do_not() {
+        assert_eq!(
+            collide(&square(0.0, 0.0, 4.0), &square(4.0, 0.0, 4.0))
+                .unwrap()
+                .depth,
+            0.0
+        );
+        assert!(
+            collide(&square(0.0, 0.0, 4.0), &square(4.1, 0.0, 4.0)).is_none()
+        );
+    }
+
+    #[test]
+    fn rotated_box_corner_touches_with_one_point() {
+        let ground = square(-10.0, 0.0, 20.0);
+        // A diamond whose bottom corner dips 1 unit into the ground.
+        let half = 2.0_f64.sqrt();
+        let diamond = Shape::Obb(Obb::new(
+            Vec2::new(0.0, 1.0 - 2.0 * half),
+            Vec2::new(2.0, 2.0),
+            FRAC_PI_4,
+        ));
+
+        let contact = collide(&diamond, &ground).unwrap();
+
+        assert!(close(contact.normal, Vec2::Y), "{contact:?}");
+        assert!((contact.depth - 1.0).abs() < 1e-9, "{contact:?}");
+        assert_eq!(contact.points().len(), 1);
+        assert!(close(contact.points()[0], Vec2::new(0.0, 1.0)));
+    }
+
+    #[test]
+    fn separated_rotated_boxes_do_not_collide() {
+        let a =
+            Shape::Obb(Obb::new(Vec2::ZERO, Vec2::new(1.0, 1.0), FRAC_PI_4));
+        let b = square(1.2, 1.2, 1.0);
+
+        assert!(a.bounds().overlaps(&b.bounds()));
+        assert!(collide(&a, &b).is_none());
+    }
+
+    #[test]
+    fn circle_against_box_from_outside_and_inside() {
+        let wall = square(0.0, 0.0, 10.0);
+        let outside = Shape::Circle(Circle::new(Vec2::new(12.0, 5.0), 3.0));
+        let inside = Shape::Circle(Circle::new(Vec2::new(5.0, 9.0), 1.0));
+
+        let contact = collide(&outside, &wall).unwrap();
+        assert_eq!(contact.normal, -Vec2::X);
+        assert_eq!(contact.depth, 1.0);
+        assert_eq!(contact.points(), [Vec2::new(10.0, 5.0)]);
+
+        let contact = collide(&wall, &inside).unwrap();
+        assert_eq!(contact.normal, Vec2::Y);
+        assert_eq!(contact.depth, 2.0);
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::collision::Aabb;
+
+/// Uniform grid broadphase. Items are bucketed by the cells their bounds
+/// cover, so only items sharing a cell are ever compared.
+#[derive(Clone, Debug)]
+pub struct SpatialHash<T> {
+    cell_size: f64,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    items: Vec<(T, Aabb)>,
+}
+
+impl<T: Copy> SpatialHash<T> {
+    /// Cells work best a little larger than a typical item.
+    pub fn new(cell_size: f64) -> Self {
+        assert!(cell_size > 0.0, "cell size must be positive");
+
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            items: Vec::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Removes every item, keeping allocations for the next frame.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        for bucket in self.cells.values_mut() {
+            bucket.clear();
+        }
+    }
+
+    pub fn insert(&mut self, item: T, bounds: Aabb) {
+        let index = self.items.len();
+        self.items.push((item, bounds));
+        for cell in self.cells_covering(&bounds) {
+            self.cells
+                .entry(cell)
+                .or_default()
+                .push(index);
+        }
+    }
+
+    /// Items whose bounds overlap `area`, in insertion order.
+    pub fn query(&self, area: &Aabb) -> Vec<T> {
+        let mut found: Vec<usize> = self
+            .cells_covering(area)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(|&index| self.items[index].1.overlaps(area))
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+            .into_iter()
+            .map(|index| self.items[index].0)
+            .collect()
+    }
+
+    /// Every pair of items with overlapping bounds, once each, ordered by
+    /// insertion so results are deterministic.
+    pub fn pairs(&self) -> Vec<(T, T)> {
+        let mut pairs = Vec::new();
+        for bucket in self.cells.values() {
+            for (i, &a) in bucket.iter().enumerate() {
+                for &b in &bucket[i + 1..] {
+                    if self.items[a]
+                        .1
+                        .overlaps(&self.items[b].1)
+                    {
+                        pairs.push((a.min(b), a.max(b)));
+                    }
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+            .into_iter()
+            .map(|(a, b)| (self.items[a].0, self.items[b].0))
+            .collect()
+    }
+
+    fn cells_covering(
+        &self,
+        bounds: &Aabb,
+    ) -> impl Iterator<Item = (i32, i32)> + use<T> {
+        let cell = |v: f64| (v / self.cell_size).floor() as i32;
+        let (x0, x1) = (cell(bounds.min.x), cell(bounds.max.x));
+        let (y0, y1) = (cell(bounds.min.y), cell(bounds.max.y));
+        (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec2;
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> Aabb {
+        Aabb::new(Vec2::new(x, y), Vec2::new(x + w, y + h))
+    }
+
+    #[test]
+    fn finds_each_overlapping_pair_once() {
+        let mut hash = SpatialHash::new(10.0);
+        // Spans four cells, overlapping both neighbours.
+        hash.insert('a', rect(5.0, 5.0, 10.0, 10.0));
+        hash.insert('b', rect(12.0, 12.0, 4.0, 4.0));
+        hash.insert('c', rect(-3.0, 8.0, 9.0, 2.0));
+        hash.insert('d', rect(40.0, 40.0, 1.0, 1.0));
+
+        assert_eq!(hash.pairs(), [('a', 'b'), ('a', 'c')]);
+        assert_eq!(hash.query(&rect(14.0, 14.0, 30.0, 30.0)), ['a', 'b', 'd']);
+
+        hash.clear();
+        assert!(hash.pairs().is_empty() && hash.is_empty());
+    }
+}
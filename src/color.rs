@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+/// 8-bit straight (non-premultiplied) RGBA color.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    #[serde(default = "opaque")]
+    pub a: u8,
+}
+
+fn opaque() -> u8 {
+    255
+}
+
+impl Color {
+    pub const TRANSPARENT: Self = Self::rgba(0, 0, 0, 0);
+    pub const BLACK: Self = Self::rgb(0, 0, 0);
+    pub const WHITE: Self = Self::rgb(255, 255, 255);
+    pub const RED: Self = Self::rgb(255, 0, 0);
+    pub const GREEN: Self = Self::rgb(0, 255, 0);
+    pub const BLUE: Self = Self::rgb(0, 0, 255);
+    pub const YELLOW: Self = Self::rgb(255, 255, 0);
+    pub const CYAN: Self = Self::rgb(0, 255, 255);
+    pub const MAGENTA: Self = Self::rgb(255, 0, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Opaque color from a softbuffer `0RGB` pixel.
+    pub const fn from_0rgb(pixel: u32) -> Self {
+        Self::rgb((pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8)
+    }
+
+    /// Packs into softbuffer's `0RGB` layout, dropping alpha.
+    pub const fn to_0rgb(self) -> u32 {
+        (self.r as u32) << 16 | (self.g as u32) << 8 | self.b as u32
+    }
+
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Self { a, ..self }
+    }
+
+    pub fn to_f32(self) -> ColorF {
+        ColorF::from(self)
+    }
+}
+
+impl From<ColorF> for Color {
+    fn from(color: ColorF) -> Self {
+        let quantize = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self::rgba(
+            quantize(color.r),
+            quantize(color.g),
+            quantize(color.b),
+            quantize(color.a),
+        )
+    }
+}
+
+/// Floating-point RGBA color with channels nominally in `[0, 1]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ColorF {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl ColorF {
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn to_u8(self) -> Color {
+        Color::from(self)
+    }
+}
+
+impl From<Color> for ColorF {
+    fn from(color: Color) -> Self {
+        Self::new(
+            color.r as f32 / 255.0,
+            color.g as f32 / 255.0,
+            color.b as f32 / 255.0,
+            color.a as f32 / 255.0,
+        )
+    }
+}
+
+/// How a drawn color combines with the pixel already in the framebuffer.
+///
+/// Every mode except [`Replace`](BlendMode::Replace) is weighted by the
+/// source alpha, so a fully transparent source leaves the pixel unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// Overwrite the destination, ignoring alpha.
+    Replace,
+    /// Standard "over" compositing.
+    #[default]
+    SourceOver,
+    /// Add the source to the destination, saturating at white.
+    Additive,
+    /// Multiply channels; darkens.
+    Multiply,
+    /// Inverse multiply of inverses; lightens.
+    Screen,
+}
+
+impl BlendMode {
+    /// Blends `src` onto an opaque `0RGB` destination pixel.
+    pub fn blend(self, src: Color, dst: u32) -> u32 {
+        if self == BlendMode::Replace
+            || (self == BlendMode::SourceOver && src.a == 255)
+        {
+            return src.to_0rgb();
+        }
+        if src.a == 0 {
+            return dst;
+        }
+
+        let dst = Color::from_0rgb(dst);
+        let channel = |s: u8, d: u8| -> u8 {
+            let (s, d) = (s as u32, d as u32);
+            let target = match self {
+                BlendMode::Replace | BlendMode::SourceOver => s,
+                BlendMode::Additive => (s + d).min(255),
+                BlendMode::Multiply => div255(s * d),
+                BlendMode::Screen => 255 - div255((255 - s) * (255 - d)),
+            };
+            let a = src.a as u32;
+            div255(target * a + d * (255 - a)) as u8
+        };
+
+        Color::rgb(
+            channel(src.r, dst.r),
+            channel(src.g, dst.g),
+            channel(src.b, dst.b),
+        )
+        .to_0rgb()
+    }
+}
+
+/// Exact rounded `x / 255` for `x <= 255 * 255`.
+fn div255(x: u32) -> u32 {
+    (x + 128 + ((x + 128) >> 8)) >> 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAY: u32 = 0x00808080;
+
+    #[test]
+    fn packs_and_unpacks_0rgb() {
+        let color = Color::rgb(0x12, 0x34, 0x56);
+
+        assert_eq!(color.to_0rgb(), 0x00123456);
+        assert_eq!(Color::from_0rgb(0xFF123456), color);
+    }
+
+    #[test]
+    fn float_conversion_round_trips() {
+        let color = Color::rgba(10, 128, 255, 64);
+
+        assert_eq!(color.to_f32().to_u8(), color);
+    }
+
+    #[test]
+    fn source_over_mixes_by_alpha() {
+        let half_white = Color::WHITE.with_alpha(128);
+
+        assert_eq!(BlendMode::SourceOver.blend(half_white, 0), 0x00808080);
+        assert_eq!(BlendMode::SourceOver.blend(Color::RED, GRAY), 0x00FF0000);
+        assert_eq!(BlendMode::SourceOver.blend(Color::TRANSPARENT, GRAY), GRAY);
+    }
+
+    #[test]
+    fn replace_ignores_alpha() {
+        let clear = Color::rgba(1, 2, 3, 0);
+
+        assert_eq!(BlendMode::Replace.blend(clear, GRAY), 0x00010203);
+    }
+
+    #[test]
+    fn additive_saturates() {
+        let src = Color::rgb(0x90, 0x10, 0x00);
+
+        assert_eq!(BlendMode::Additive.blend(src, GRAY), 0x00FF9080);
+    }
+
+    #[test]
+    fn multiply_and_screen_darken_and_lighten() {
+        let src = Color::rgb(0x80, 0xFF, 0x00);
+
+        assert_eq!(BlendMode::Multiply.blend(src, GRAY), 0x00408000);
+        assert_eq!(BlendMode::Screen.blend(src, GRAY), 0x00C0FF80);
+    }
+}
@@ -0,0 +1,203 @@
+//! Components used by the built-in systems.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets::Handle;
+use crate::collision::Shape;
+use crate::color::Color;
+use crate::ecs::Entity;
+use crate::image::Image;
+use crate::transform::Transform2D;
+
+/// Top-left corner of [`Bounds`] in world units. [`Collider`] shapes
+/// and [`Rotation`] are relative to it too.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Position at the start of the current tick, so rendering can
+/// interpolate towards [`Position`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PreviousPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// World units per second.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Velocity {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Size of the axis-aligned box extending right and down from
+/// [`Position`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub w: f64,
+    pub h: f64,
+}
+
+/// Solid color to draw an entity's bounds with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fill(pub Color);
+
+/// Moved by the `move_x`/`move_y` input axes at `speed` world units per
+/// second, and teleported to the cursor by the `teleport` action.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerControlled {
+    pub speed: f64,
+}
+
+/// Collision shape in coordinates relative to [`Position`]. Entities
+/// without a [`Velocity`] are treated as immovable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Collider(pub Shape);
+
+/// Clockwise rotation in radians about [`Position`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rotation(pub f64);
+
+/// Radians per second, clockwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AngularVelocity(pub f64);
+
+/// Simulated by [`step_physics`] with its [`Collider`] centered on
+/// [`Position`], which is used as the center of mass.
+///
+/// [`step_physics`]: crate::physics::step_physics
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RigidBody {
+    /// Kilograms, or zero for a body that never moves.
+    pub mass: f64,
+    /// Fraction of the approach speed kept after an impact, 0..=1.
+    #[serde(default)]
+    pub restitution: f64,
+    /// Coulomb friction coefficient.
+    #[serde(default = "default_friction")]
+    pub friction: f64,
+    /// Locks rotation, e.g. for characters.
+    #[serde(default)]
+    pub fixed_rotation: bool,
+    #[serde(skip)]
+    asleep: bool,
+    #[serde(skip)]
+    still_for: f64,
+}
+
+impl RigidBody {
+    pub fn dynamic(mass: f64) -> Self {
+        Self {
+            mass,
+            restitution: 0.0,
+            friction: default_friction(),
+            fixed_rotation: false,
+            asleep: false,
+            still_for: 0.0,
+        }
+    }
+
+    /// Infinitely heavy, e.g. the ground.
+    pub fn fixed() -> Self {
+        Self::dynamic(0.0)
+    }
+
+    pub fn with_restitution(mut self, restitution: f64) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    pub fn with_friction(mut self, friction: f64) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    pub fn with_fixed_rotation(mut self) -> Self {
+        self.fixed_rotation = true;
+        self
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        self.mass > 0.0
+    }
+
+    /// Asleep bodies skip integration until something hits them.
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    pub fn wake(&mut self) {
+        self.asleep = false;
+        self.still_for = 0.0;
+    }
+
+    /// Counts how long the body has been still, putting it to sleep
+    /// after `delay` seconds. Returns whether it is now asleep.
+    pub(crate) fn settle(&mut self, still: bool, dt: f64, delay: f64) -> bool {
+        if !still {
+            self.still_for = 0.0;
+            return false;
+        }
+        self.still_for += dt;
+        self.asleep = self.still_for >= delay;
+        self.asleep
+    }
+}
+
+/// Makes an entity's [`LocalTransform`] relative to another entity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parent(pub Entity);
+
+/// Transform relative to the [`Parent`], or to the world for roots.
+/// Parents without one are placed by their [`Position`] and
+/// [`Rotation`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LocalTransform(pub Transform2D);
+
+/// World transform of an entity with a [`LocalTransform`], written by
+/// [`propagate_transforms`] each tick.
+///
+/// [`propagate_transforms`]: crate::systems::propagate_transforms
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GlobalTransform(pub Transform2D);
+
+/// Shape drawn at an entity's world transform. Unlike a [`Collider`],
+/// it takes no part in collisions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Drawable(pub Shape);
+
+/// Text drawn from an entity's world position, scaled with it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Label {
+    pub text: String,
+    #[serde(default = "white")]
+    pub color: Color,
+}
+
+/// Identifies an entity in scene files, e.g. as a [`Parent`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Name(pub String);
+
+/// Image drawn stretched over an entity's [`Bounds`] instead of its
+/// [`Fill`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sprite {
+    /// Where the image was loaded from, as written in the scene.
+    pub path: PathBuf,
+    /// Image in the world's [`Assets`], so it follows file changes.
+    ///
+    /// [`Assets`]: crate::assets::Assets
+    pub image: Handle<Image>,
+}
+
+fn default_friction() -> f64 {
+    0.5
+}
+
+fn white() -> Color {
+    Color::WHITE
+}
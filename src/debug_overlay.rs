@@ -0,0 +1,283 @@
+//! Toggleable on-screen frame timing, state readout and recent log.
+
+use std::collections::VecDeque;
+
+use crate::color::Color;
+use crate::dpi::ui_scale;
+use crate::font::BitmapFont;
+use crate::framebuffer::Framebuffer;
+use crate::logging::{Level, Record};
+use crate::text::{TextStyle, measure_text};
+
+/// Number of recent frames kept for the statistics and graph.
+const HISTORY_LEN: usize = 120;
+
+const MARGIN: i32 = 4;
+const PADDING: i32 = 4;
+const GRAPH_HEIGHT: i32 = 40;
+/// Frame time shown at the top of the graph, in seconds.
+const GRAPH_MAX: f64 = 1.0 / 20.0;
+/// Frame time drawn as a reference line on the graph (60 Hz).
+const GRAPH_TARGET: f64 = 1.0 / 60.0;
+
+const PANEL_COLOR: Color = Color::rgba(0, 0, 0, 176);
+const TEXT_COLOR: Color = Color::WHITE;
+const TARGET_COLOR: Color = Color::rgba(255, 255, 255, 96);
+const BAR_COLOR: Color = Color::GREEN;
+const SLOW_BAR_COLOR: Color = Color::RED;
+const WARN_TEXT_COLOR: Color = Color::YELLOW;
+const ERROR_TEXT_COLOR: Color = Color::RED;
+
+/// Rolling window of recent frame times.
+#[derive(Clone, Debug, Default)]
+pub struct FrameStats {
+    frame_times: VecDeque<f64>,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one frame that took `frame_time` seconds, dropping the
+    /// oldest sample once the history is full.
+    pub fn record(&mut self, frame_time: f64) {
+        if self.frame_times.len() == HISTORY_LEN {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    /// Recorded frame times, oldest first.
+    pub fn frame_times(&self) -> impl ExactSizeIterator<Item = f64> + '_ {
+        self.frame_times.iter().copied()
+    }
+
+    /// Shortest recorded frame time, or 0 with no samples.
+    pub fn min(&self) -> f64 {
+        self.frame_times()
+            .reduce(f64::min)
+            .unwrap_or(0.0)
+    }
+
+    pub fn max(&self) -> f64 {
+        self.frame_times().fold(0.0, f64::max)
+    }
+
+    pub fn average(&self) -> f64 {
+        self.frame_times().sum::<f64>() / self.frame_times.len().max(1) as f64
+    }
+
+    /// Frames per second implied by the average frame time.
+    pub fn fps(&self) -> f64 {
+        let average = self.average();
+        if average > 0.0 { 1.0 / average } else { 0.0 }
+    }
+}
+
+/// Values shown by the overlay that it does not measure itself.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DebugInfo {
+    pub window_size: (u32, u32),
+    pub scale_factor: f64,
+    pub square_position: (f64, f64),
+    pub square_velocity: (f64, f64),
+}
+
+/// Frame-time readout and graph drawn over the top-left of the frame.
+#[derive(Clone, Debug)]
+pub struct DebugOverlay {
+    pub visible: bool,
+    pub stats: FrameStats,
+    font: BitmapFont,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugOverlay {
+    /// A hidden overlay using the built-in 8x8 font.
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            stats: FrameStats::new(),
+            font: BitmapFont::builtin_8x8(),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Draws the overlay if it is visible, magnified by the whole part
+    /// of the scale factor.
+    pub fn draw(&self, framebuffer: &mut Framebuffer, info: &DebugInfo) {
+        if !self.visible {
+            return;
+        }
+
+        let stats = &self.stats;
+        let text = format!(
+            "FPS {:.1}\n\
+             frame ms min {:.2} avg {:.2} max {:.2}\n\
+             window {}x{} @ {:.2}x\n\
+             pos {:.1}, {:.1}\n\
+             vel {:.1}, {:.1}",
+            stats.fps(),
+            stats.min() * 1000.0,
+            stats.average() * 1000.0,
+            stats.max() * 1000.0,
+            info.window_size.0,
+            info.window_size.1,
+            info.scale_factor,
+            info.square_position.0,
+            info.square_position.1,
+            info.square_velocity.0,
+            info.square_velocity.1,
+        );
+        let scale = ui_scale(info.scale_factor);
+        let px = scale as i32;
+        let (margin, padding) = (MARGIN * px, PADDING * px);
+        let graph_height = GRAPH_HEIGHT * px;
+        let style = TextStyle {
+            color: TEXT_COLOR,
+            scale,
+            ..TextStyle::default()
+        };
+        let (text_w, text_h) = measure_text(&self.font, &text, &style);
+
+        let graph_w = HISTORY_LEN as i32 * px;
+        let panel_w = text_w.max(graph_w) + padding * 2;
+        let panel_h = text_h + padding * 3 + graph_height;
+        framebuffer.fill_rect(margin, margin, panel_w, panel_h, PANEL_COLOR);
+
+        let left = margin + padding;
+        framebuffer.draw_text(
+            &self.font,
+            &text,
+            left,
+            margin + padding,
+            &style,
+        );
+
+        let graph_bottom = margin + panel_h - padding;
+        for (i, frame_time) in stats.frame_times().enumerate() {
+            let height = (frame_time / GRAPH_MAX * GRAPH_HEIGHT as f64)
+                .ceil()
+                .clamp(1.0, GRAPH_HEIGHT as f64)
+                as i32
+                * px;
+            let color = if frame_time > GRAPH_TARGET {
+                SLOW_BAR_COLOR
+            } else {
+                BAR_COLOR
+            };
+            framebuffer.fill_rect(
+                left + i as i32 * px,
+                graph_bottom - height,
+                px,
+                height,
+                color,
+            );
+        }
+
+        let target_y = graph_bottom
+            - (GRAPH_TARGET / GRAPH_MAX * GRAPH_HEIGHT as f64).round() as i32
+                * px;
+        framebuffer.fill_rect(left, target_y, graph_w, px, TARGET_COLOR);
+    }
+
+    /// Lists `records` along the bottom of the frame, newest last, if the
+    /// overlay is visible.
+    pub fn draw_log(
+        &self,
+        framebuffer: &mut Framebuffer,
+        records: &[Record],
+        scale_factor: f64,
+    ) {
+        if !self.visible || records.is_empty() {
+            return;
+        }
+
+        let scale = ui_scale(scale_factor);
+        let px = scale as i32;
+        let (margin, padding) = (MARGIN * px, PADDING * px);
+        let panel_w = framebuffer.width() as i32 - margin * 2;
+        let text_w = panel_w - padding * 2;
+        if text_w <= 0 {
+            return;
+        }
+        let style = TextStyle {
+            scale,
+            max_width: Some(text_w),
+            ..TextStyle::default()
+        };
+        let lines: Vec<_> = records
+            .iter()
+            .map(|record| {
+                let text = format!(
+                    "{:>5} {} {}",
+                    record.frame, record.level, record.message
+                );
+                let color = match record.level {
+                    Level::Error => ERROR_TEXT_COLOR,
+                    Level::Warn => WARN_TEXT_COLOR,
+                    _ => TEXT_COLOR,
+                };
+                let height = measure_text(&self.font, &text, &style).1;
+                (text, color, height)
+            })
+            .collect();
+
+        let text_h: i32 = lines.iter().map(|(_, _, h)| h).sum();
+        let panel_h = text_h + padding * 2;
+        let top = framebuffer.height() as i32 - margin - panel_h;
+        framebuffer.fill_rect(margin, top, panel_w, panel_h, PANEL_COLOR);
+
+        let mut y = top + padding;
+        for (text, color, height) in lines {
+            let style = TextStyle { color, ..style };
+            framebuffer.draw_text(
+                &self.font,
+                &text,
+                margin + padding,
+                y,
+                &style,
+            );
+            y += height;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_stats_read_as_zero() {
+        let stats = FrameStats::new();
+
+        assert_eq!(stats.min(), 0.0);
+        assert_eq!(stats.max(), 0.0);
+        assert_eq!(stats.average(), 0.0);
+        assert_eq!(stats.fps(), 0.0);
+    }
+
+    #[test]
+    fn keeps_a_rolling_window() {
+        let mut stats = FrameStats::new();
+        stats.record(1.0);
+        for _ in 0..HISTORY_LEN {
+            stats.record(0.25);
+        }
+        stats.record(0.5);
+
+        assert_eq!(stats.frame_times().len(), HISTORY_LEN);
+        assert_eq!(stats.min(), 0.25);
+        assert_eq!(stats.max(), 0.5);
+        assert_eq!(stats.fps(), 1.0 / stats.average());
+    }
+}
@@ -0,0 +1,75 @@
+//! Physical and logical window pixels on HiDPI displays.
+
+/// Window size in physical pixels plus the OS scale factor, which is the
+/// number of physical pixels per logical one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowMetrics {
+    pub physical_size: (usize, usize),
+    pub scale_factor: f64,
+}
+
+impl WindowMetrics {
+    pub fn new(width: usize, height: usize, scale_factor: f64) -> Self {
+        Self {
+            physical_size: (width, height),
+            scale_factor: sanitize(scale_factor),
+        }
+    }
+
+    pub fn logical_size(&self) -> (f64, f64) {
+        let (width, height) = self.physical_size;
+        (width as f64 / self.scale_factor, height as f64 / self.scale_factor)
+    }
+
+    pub fn to_logical(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (x / self.scale_factor, y / self.scale_factor)
+    }
+
+    pub fn to_physical(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (x * self.scale_factor, y * self.scale_factor)
+    }
+
+    /// Integer magnification for pixel-art UI such as bitmap text.
+    pub fn ui_scale(&self) -> u32 {
+        ui_scale(self.scale_factor)
+    }
+}
+
+/// Largest whole magnification not above `scale_factor`, so bitmap
+/// glyphs stay crisp and UI never outgrows its intended physical size.
+pub fn ui_scale(scale_factor: f64) -> u32 {
+    (sanitize(scale_factor).floor() as u32).max(1)
+}
+
+/// Falls back to 1x for the zero or NaN factors a headless or broken
+/// platform might report.
+fn sanitize(scale_factor: f64) -> f64 {
+    if scale_factor.is_finite() && scale_factor > 0.0 {
+        scale_factor
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_logical_and_physical() {
+        let metrics = WindowMetrics::new(1600, 1200, 2.0);
+
+        assert_eq!(metrics.logical_size(), (800.0, 600.0));
+        assert_eq!(metrics.to_logical((30.0, 10.0)), (15.0, 5.0));
+        assert_eq!(metrics.to_physical((15.0, 5.0)), (30.0, 10.0));
+    }
+
+    #[test]
+    fn ui_scale_rounds_down_to_whole_pixels() {
+        assert_eq!(ui_scale(1.0), 1);
+        assert_eq!(ui_scale(1.5), 1);
+        assert_eq!(ui_scale(2.25), 2);
+        assert_eq!(ui_scale(0.0), 1);
+        assert_eq!(WindowMetrics::new(1, 1, f64::NAN).scale_factor, 1.0);
+    }
+}
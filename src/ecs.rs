@@ -0,0 +1,509 @@
+//! Entities, typed component storage, tuple queries and system schedules.
+//!
+//! Any `'static` type can be a component or a resource. Storages live in
+//! `RefCell`s so a query can mutably borrow several component types from
+//! a shared `&World` at once.
+
+use std::any::{Any, TypeId};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+
+/// Handle to an entity. Stale handles to despawned entities are detected
+/// through the generation, even after the slot is reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    /// Slot of the entity within the world's storages.
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+/// Components of one type, indexed by entity slot.
+pub struct Storage<T> {
+    slots: Vec<Option<T>>,
+}
+
+impl<T> Storage<T> {
+    fn get(&self, index: usize) -> Option<&T> {
+        self.slots.get(index)?.as_ref()
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.slots.get_mut(index)?.as_mut()
+    }
+
+    fn insert(&mut self, index: usize, component: T) {
+        if self.slots.len() <= index {
+            self.slots
+                .resize_with(index + 1, || None);
+        }
+        self.slots[index] = Some(component);
+    }
+
+    fn remove(&mut self, index: usize) -> Option<T> {
+        self.slots.get_mut(index)?.take()
+    }
+}
+
+/// Type-erased `RefCell<Storage<T>>`.
+trait AnyStorage {
+    fn as_any(&self) -> &dyn Any;
+
+    fn remove_slot(&mut self, index: usize);
+}
+
+impl<T: 'static> AnyStorage for RefCell<Storage<T>> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn remove_slot(&mut self, index: usize) {
+        self.get_mut().remove(index);
+    }
+}
+
+/// Container for entities, their components, and global resources.
+#[derive(Default)]
+pub struct World {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+    resources: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an entity, reusing a despawned slot when one is free.
+    pub fn spawn(&mut self) -> EntityBuilder<'_> {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.generations.push(0);
+                self.alive.push(false);
+                (self.generations.len() - 1) as u32
+            }
+        };
+        self.alive[index as usize] = true;
+        let entity = Entity {
+            index,
+            generation: self.generations[index as usize],
+        };
+
+        EntityBuilder { world: self, entity }
+    }
+
+    /// Removes an entity and all its components. Returns `false` if it
+    /// was already gone.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        let index = entity.index();
+        for storage in self.storages.values_mut() {
+            storage.remove_slot(index);
+        }
+        self.alive[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(entity.index);
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let index = entity.index();
+        self.alive
+            .get(index)
+            .is_some_and(|&alive| alive)
+            && self.generations[index] == entity.generation
+    }
+
+    /// Number of live entities.
+    pub fn len(&self) -> usize {
+        self.generations.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Live entities in slot order.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.alive
+            .iter()
+            .enumerate()
+            .filter(|(_, alive)| **alive)
+            .map(|(index, _)| Entity {
+                index: index as u32,
+                generation: self.generations[index],
+            })
+    }
+
+    /// Adds or replaces a component. Ignored for despawned entities.
+    pub fn insert<T: 'static>(&mut self, entity: Entity, component: T) {
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| {
+                Box::new(RefCell::new(Storage::<T> { slots: Vec::new() }))
+            })
+            .as_any()
+            .downcast_ref::<RefCell<Storage<T>>>()
+            .expect("storage is keyed by its component type")
+            .borrow_mut()
+            .insert(entity.index(), component);
+    }
+
+    pub fn remove<T: 'static>(&mut self, entity: Entity) -> Option<T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.storage::<T>()?
+            .borrow_mut()
+            .remove(entity.index())
+    }
+
+    pub fn get<T: 'static>(&self, entity: Entity) -> Option<Ref<'_, T>> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        let storage = self.storage::<T>()?.borrow();
+        Ref::filter_map(storage, |storage| storage.get(entity.index())).ok()
+    }
+
+    pub fn get_mut<T: 'static>(&self, entity: Entity) -> Option<RefMut<'_, T>> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        let storage = self.storage::<T>()?.borrow_mut();
+        RefMut::filter_map(storage, |storage| storage.get_mut(entity.index()))
+            .ok()
+    }
+
+    /// Calls `f` for every live entity that matches `Q`, e.g.
+    /// `(&mut Position, &Velocity, Option<&Bounds>)`.
+    ///
+    /// # Panics
+    ///
+    /// If a component type in `Q` is already mutably borrowed, or is
+    /// borrowed mutably while also read elsewhere.
+    pub fn query<Q: Query>(&self, mut f: impl FnMut(Entity, Q::Item<'_>)) {
+        let Some(mut fetch) = Q::fetch(self) else {
+            return;
+        };
+        for entity in self.entities() {
+            if let Some(item) = Q::get(&mut fetch, entity.index()) {
+                f(entity, item);
+            }
+        }
+    }
+
+    /// Adds or replaces the resource of type `R`.
+    pub fn insert_resource<R: 'static>(&mut self, resource: R) {
+        self.resources
+            .insert(TypeId::of::<R>(), Box::new(RefCell::new(resource)));
+    }
+
+    pub fn remove_resource<R: 'static>(&mut self) -> Option<R> {
+        let resource = self
+            .resources
+            .remove(&TypeId::of::<R>())?;
+        let cell = resource
+            .downcast::<RefCell<R>>()
+            .expect("resource is keyed by its type");
+        Some(cell.into_inner())
+    }
+
+    pub fn resource<R: 'static>(&self) -> Option<Ref<'_, R>> {
+        Some(self.resource_cell::<R>()?.borrow())
+    }
+
+    pub fn resource_mut<R: 'static>(&self) -> Option<RefMut<'_, R>> {
+        Some(self.resource_cell::<R>()?.borrow_mut())
+    }
+
+    fn resource_cell<R: 'static>(&self) -> Option<&RefCell<R>> {
+        self.resources
+            .get(&TypeId::of::<R>())?
+            .downcast_ref()
+    }
+
+    fn storage<T: 'static>(&self) -> Option<&RefCell<Storage<T>>> {
+        self.storages
+            .get(&TypeId::of::<T>())?
+            .as_any()
+            .downcast_ref()
+    }
+}
+
+/// Returned by [`World::spawn`] to attach components to a new entity.
+pub struct EntityBuilder<'w> {
+    world: &'w mut World,
+    entity: Entity,
+}
+
+impl EntityBuilder<'_> {
+    pub fn with<T: 'static>(self, component: T) -> Self {
+        self.world
+            .insert(self.entity, component);
+        self
+    }
+
+    pub fn id(&self) -> Entity {
+        self.entity
+    }
+}
+
+/// A set of component borrows that [`World::query`] can iterate.
+///
+/// Implemented for `&T`, `&mut T`, `Option<Q>` and tuples of up to six
+/// queries.
+pub trait Query {
+    /// Borrowed storages, held for the whole query.
+    type Fetch<'w>;
+    /// What the query yields for one entity.
+    type Item<'f>;
+
+    /// Borrows the storages, or `None` if a required one doesn't exist.
+    fn fetch(world: &World) -> Option<Self::Fetch<'_>>;
+
+    fn get<'f>(
+        fetch: &'f mut Self::Fetch<'_>,
+        index: usize,
+    ) -> Option<Self::Item<'f>>;
+}
+
+impl<T: 'static> Query for &T {
+    type Fetch<'w> = Ref<'w, Storage<T>>;
+    type Item<'f> = &'f T;
+
+    fn fetch(world: &World) -> Option<Self::Fetch<'_>> {
+        Some(world.storage::<T>()?.borrow())
+    }
+
+    fn get<'f>(
+        fetch: &'f mut Self::Fetch<'_>,
+        index: usize,
+    ) -> Option<Self::Item<'f>> {
+        fetch.get(index)
+    }
+}
+
+impl<T: 'static> Query for &mut T {
+    type Fetch<'w> = RefMut<'w, Storage<T>>;
+    type Item<'f> = &'f mut T;
+
+    fn fetch(world: &World) -> Option<Self::Fetch<'_>> {
+        Some(world.storage::<T>()?.borrow_mut())
+    }
+
+    fn get<'f>(
+        fetch: &'f mut Self::Fetch<'_>,
+        index: usize,
+    ) -> Option<Self::Item<'f>> {
+        fetch.get_mut(index)
+    }
+}
+
+impl<Q: Query> Query for Option<Q> {
+    type Fetch<'w> = Option<Q::Fetch<'w>>;
+    type Item<'f> = Option<Q::Item<'f>>;
+
+    fn fetch(world: &World) -> Option<Self::Fetch<'_>> {
+        Some(Q::fetch(world))
+    }
+
+    fn get<'f>(
+        fetch: &'f mut Self::Fetch<'_>,
+        index: usize,
+    ) -> Option<Self::Item<'f>> {
+        Some(
+            fetch
+                .as_mut()
+                .and_then(|fetch| Q::get(fetch, index)),
+        )
+    }
+}
+
+macro_rules! impl_query_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Query),+> Query for ($($name,)+) {
+            type Fetch<'w> = ($($name::Fetch<'w>,)+);
+            type Item<'f> = ($($name::Item<'f>,)+);
+
+            fn fetch(world: &World) -> Option<Self::Fetch<'_>> {
+                Some(($($name::fetch(world)?,)+))
+            }
+
+            #[allow(non_snake_case)]
+            fn get<'f>(
+                fetch: &'f mut Self::Fetch<'_>,
+                index: usize,
+            ) -> Option<Self::Item<'f>> {
+                let ($($name,)+) = fetch;
+                Some(($($name::get($name, index)?,)+))
+            }
+        }
+    };
+}
+
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, F);
+
+type System = Box<dyn FnMut(&mut World)>;
+
+/// Named systems run in insertion order.
+#[derive(Default)]
+pub struct Schedule {
+    systems: Vec<(&'static str, System)>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_system(
+        mut self,
+        name: &'static str,
+        system: impl FnMut(&mut World) + 'static,
+    ) -> Self {
+        self.add_system(name, system);
+        self
+    }
+
+    pub fn add_system(
+        &mut self,
+        name: &'static str,
+        system: impl FnMut(&mut World) + 'static,
+    ) {
+        self.systems
+            .push((name, Box::new(system)));
+    }
+
+    pub fn system_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.systems
+            .iter()
+            .map(|(name, _)| *name)
+    }
+
+    /// Runs every system once, in order.
+    pub fn run(&mut self, world: &mut World) {
+        for (_, system) in &mut self.systems {
+            system(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Pos(i32);
+    #[derive(Debug, PartialEq)]
+    struct Vel(i32);
+    struct Frozen;
+
+    #[test]
+    fn despawned_handles_go_stale_when_slots_are_reused() {
+        let mut world = World::new();
+        let first = world.spawn().with(Pos(1)).id();
+        assert!(world.despawn(first));
+
+        let second = world.spawn().id();
+
+        assert_eq!(first.index(), second.index());
+        assert!(!world.is_alive(first));
+        assert!(world.get::<Pos>(second).is_none());
+        assert!(!world.despawn(first));
+        assert_eq!(world.len(), 1);
+    }
+
+    #[test]
+    fn queries_match_entities_with_every_component() {
+        let mut world = World::new();
+        let moving = world
+            .spawn()
+            .with(Pos(0))
+            .with(Vel(2))
+            .id();
+        world.spawn().with(Pos(10));
+        world
+            .spawn()
+            .with(Pos(20))
+            .with(Vel(5))
+            .with(Frozen);
+
+        world.query::<(&mut Pos, &Vel, Option<&Frozen>)>(
+            |_, (pos, vel, frozen)| {
+                if frozen.is_none() {
+                    pos.0 += vel.0;
+                }
+            },
+        );
+
+        let mut seen = Vec::new();
+        world.query::<&Pos>(|entity, pos| seen.push((entity, pos.0)));
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[0], (moving, 2));
+        assert_eq!(seen[1].1, 10);
+        assert_eq!(seen[2].1, 20);
+    }
+
+    #[test]
+    #[should_panic]
+    fn conflicting_borrows_panic() {
+        let mut world = World::new();
+        world.spawn().with(Pos(0));
+
+        world.query::<(&mut Pos, &Pos)>(|_, _| {});
+    }
+
+    #[test]
+    fn schedule_runs_systems_in_order_with_resources() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&str>::new());
+        let mut schedule = Schedule::new()
+            .with_system("first", |world| {
+                world
+                    .resource_mut::<Vec<&str>>()
+                    .unwrap()
+                    .push("first")
+            })
+            .with_system("second", |world| {
+                world
+                    .resource_mut::<Vec<&str>>()
+                    .unwrap()
+                    .push("second")
+            });
+
+        schedule.run(&mut world);
+
+        assert_eq!(
+            *world.resource::<Vec<&str>>().unwrap(),
+            ["first", "second"]
+        );
+        assert_eq!(
+            schedule
+                .system_names()
+                .collect::<Vec<_>>(),
+            ["first", "second"]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Errors that stop the engine: windowing, presentation and the files a
+//! session reads or writes.
+
+use std::fmt;
+use std::io;
+
+use softbuffer::SoftBufferError;
+use winit::error::OsError;
+
+use crate::assets::AssetError;
+use crate::input::InputError;
+use crate::scene::SceneError;
+
+#[derive(Debug)]
+pub enum Error {
+    Window(OsError),
+    Surface(SoftBufferError),
+    Input(InputError),
+    Scene(SceneError),
+    Asset(AssetError),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Window(err) => write!(f, "failed to create window: {err}"),
+            Self::Surface(err) => write!(f, "window surface failed: {err}"),
+            Self::Input(err) => err.fmt(f),
+            Self::Scene(err) => err.fmt(f),
+            Self::Asset(err) => err.fmt(f),
+            Self::Io(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Window(err) => Some(err),
+            Self::Surface(err) => Some(err),
+            Self::Input(err) => Some(err),
+            Self::Scene(err) => Some(err),
+            Self::Asset(err) => Some(err),
+            Self::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<OsError> for Error {
+    fn from(err: OsError) -> Self {
+        Self::Window(err)
+    }
+}
+
+impl From<SoftBufferError> for Error {
+    fn from(err: SoftBufferError) -> Self {
+        Self::Surface(err)
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(err: InputError) -> Self {
+        Self::Input(err)
+    }
+}
+
+impl From<SceneError> for Error {
+    fn from(err: SceneError) -> Self {
+        Self::Scene(err)
+    }
+}
+
+impl From<AssetError> for Error {
+    fn from(err: AssetError) -> Self {
+        Self::Asset(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
@@ -0,0 +1,62 @@
+//! Fonts that can be drawn with [`Framebuffer::draw_text`].
+//!
+//! [`Framebuffer::draw_text`]: crate::framebuffer::Framebuffer::draw_text
+
+mod bitmap;
+mod font8x8;
+mod truetype;
+
+use std::fmt;
+use std::io;
+
+use crate::image::ImageError;
+
+pub use bitmap::{BitmapFont, Glyph};
+pub use truetype::{FontId, GlyphCache, SizedFont, TrueTypeFont};
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    Image(ImageError),
+    /// The data is not a font the TrueType/OpenType parser understands.
+    Invalid(String),
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read font: {err}"),
+            Self::Image(err) => write!(f, "failed to load font page: {err}"),
+            Self::Invalid(message) => write!(f, "invalid font: {message}"),
+            Self::Parse { line, message } => {
+                write!(f, "font descriptor line {line}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FontError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Image(err) => Some(err),
+            Self::Invalid(_) | Self::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for FontError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ImageError> for FontError {
+    fn from(err: ImageError) -> Self {
+        Self::Image(err)
+    }
+}
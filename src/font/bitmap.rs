@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::blit::BlitOptions;
+use crate::color::Color;
+use crate::font::FontError;
+use crate::font::font8x8;
+use crate::framebuffer::Framebuffer;
+use crate::image::Image;
+use crate::rect::Rect;
+use crate::text::Font;
+
+const ATLAS_COLUMNS: usize = 16;
+/// Most pages a BMFont may declare.
+const MAX_PAGES: i32 = 256;
+
+/// Placement of one character inside a font page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Glyph {
+    pub page: usize,
+    pub source: Rect,
+    /// Offset from the pen position to the glyph's top-left corner.
+    pub offset_x: i32,
+    pub offset_y: i32,
+    pub advance: i32,
+}
+
+/// Font drawn from pre-rendered glyph images.
+#[derive(Clone, Debug)]
+pub struct BitmapFont {
+    pages: Vec<Image>,
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), i32>,
+    line_height: i32,
+    /// Drawn in place of characters the font has no glyph for.
+    fallback: Option<char>,
+}
+
+impl BitmapFont {
+    /// Built-in 8x8 font covering printable ASCII.
+    pub fn builtin_8x8() -> Self {
+        Self::builtin(1)
+    }
+
+    /// The built-in font with every row doubled, for an 8x16 cell.
+    pub fn builtin_8x16() -> Self {
+        Self::builtin(2)
+    }
+
+    fn builtin(row_repeat: usize) -> Self {
+        let cell_h = 8 * row_repeat;
+        let rows = font8x8::GLYPHS
+            .len()
+            .div_ceil(ATLAS_COLUMNS);
+        let mut atlas = Image::new(ATLAS_COLUMNS * 8, rows * cell_h);
+        let mut glyphs = HashMap::new();
+
+        for (i, bitmap) in font8x8::GLYPHS.iter().enumerate() {
+            let cell_x = (i % ATLAS_COLUMNS) * 8;
+            let cell_y = (i / ATLAS_COLUMNS) * cell_h;
+
+            for (row, bits) in bitmap.iter().enumerate() {
+                for col in (0..8).filter(|col| bits & (1 << col) != 0) {
+                    for repeat in 0..row_repeat {
+                        let y = cell_y + row * row_repeat + repeat;
+                        atlas.set_pixel(cell_x + col, y, Color::WHITE);
+                    }
+                }
+            }
+
+            let ch = char::from_u32(font8x8::FIRST_CHAR as u32 + i as u32)
+                .expect("built-in glyphs are ASCII");
+            let source =
+                Rect::new(cell_x as i32, cell_y as i32, 8, cell_h as i32);
+            glyphs.insert(
+                ch,
+                Glyph {
+                    page: 0,
+                    source,
+                    offset_x: 0,
+                    offset_y: 0,
+                    advance: 8,
+                },
+            );
+        }
+
+        Self {
+            pages: vec![atlas],
+            glyphs,
+            kerning: HashMap::new(),
+            line_height: cell_h as i32,
+            fallback: Some('?'),
+        }
+    }
+
+    /// Loads an AngelCode BMFont text descriptor (`.fnt`), resolving its
+    /// page images relative to the descriptor.
+    pub fn load_bmfont(path: impl AsRef<Path>) -> Result<Self, FontError> {
+        let path = path.as_ref();
+        let descriptor = fs::read_to_string(path)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+
+        Self::parse_bmfont(&descriptor, |file| {
+            Image::load(dir.join(file)).map_err(FontError::from)
+        })
+    }
+
+    /// Parses a BMFont text descriptor, loading each page with `load_page`.
+    pub fn parse_bmfont(
+        descriptor: &str,
+        mut load_page: impl FnMut(&str) -> Result<Image, FontError>,
+    ) -> Result<Self, FontError> {
+        let mut font = Self {
+            pages: Vec::new(),
+            glyphs: HashMap::new(),
+            kerning: HashMap::new(),
+            line_height: 0,
+            fallback: None,
+        };
+        let mut page_files = Vec::new();
+
+        for (index, line) in descriptor.lines().enumerate() {
+            let line_number = index + 1;
+            let Some((tag, rest)) = line.trim().split_once(' ') else {
+                continue;
+            };
+            let attrs = BmAttributes::parse(rest, line_number)?;
+
+            match tag {
+                "common" => font.line_height = attrs.int("lineHeight")?,
+                "page" => {
+                    let id = attrs.int("id")?;
+                    if !(0..MAX_PAGES).contains(&id) {
+                        return Err(attrs.error(format!(
+                            "page id {id} is not in 0..{MAX_PAGES}"
+                        )));
+                    }
+                    let id = id as usize;
+                    if page_files.len() <= id {
+                        page_files.resize(id + 1, None);
+                    }
+                    page_files[id] = Some(attrs.string("file")?.to_owned());
+                }
+                "char" => {
+                    let id = attrs.int("id")?;
+                    let ch = char::from_u32(id as u32).ok_or_else(|| {
+                        attrs.error(format!("invalid char id {id}"))
+                    })?;
+                    let glyph = Glyph {
+                        page: attrs.int("page").unwrap_or(0) as usize,
+                        source: Rect::new(
+                            attrs.int("x")?,
+                            attrs.int("y")?,
+                            attrs.int("width")?,
+                            attrs.int("height")?,
+                        ),
+                        offset_x: attrs.int("xoffset")?,
+                        offset_y: attrs.int("yoffset")?,
+                        advance: attrs.int("xadvance")?,
+                    };
+                    font.glyphs.insert(ch, glyph);
+                }
+                "kerning" => {
+                    let pair = [attrs.int("first")?, attrs.int("second")?]
+                        .map(|id| char::from_u32(id as u32));
+                    if let [Some(first), Some(second)] = pair {
+                        font.kerning
+                            .insert((first, second), attrs.int("amount")?);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (id, file) in page_files.into_iter().enumerate() {
+            let file = file.ok_or_else(|| FontError::Parse {
+                line: 0,
+                message: format!("page {id} is not declared"),
+            })?;
+            font.pages.push(load_page(&file)?);
+        }
+        if let Some((ch, _)) = font
+            .glyphs
+            .iter()
+            .find(|(_, g)| g.page >= font.pages.len())
+        {
+            return Err(FontError::Parse {
+                line: 0,
+                message: format!("glyph {ch:?} refers to a missing page"),
+            });
+        }
+        font.fallback = ['\u{FFFD}', '?']
+            .into_iter()
+            .find(|ch| font.glyphs.contains_key(ch));
+
+        Ok(font)
+    }
+
+    pub fn line_height(&self) -> i32 {
+        self.line_height
+    }
+
+    /// Glyph for `ch`, or the fallback glyph if the font lacks it.
+    pub fn glyph(&self, ch: char) -> Option<&Glyph> {
+        self.glyphs
+            .get(&ch)
+            .or_else(|| self.glyphs.get(&self.fallback?))
+    }
+}
+
+impl Font for BitmapFont {
+    fn line_height(&self, scale: u32) -> i32 {
+        self.line_height * scale as i32
+    }
+
+    fn advance(&self, ch: char, scale: u32) -> i32 {
+        self.glyph(ch)
+            .map_or(0, |glyph| glyph.advance * scale as i32)
+    }
+
+    fn kerning(&self, left: char, right: char, scale: u32) -> i32 {
+        self.kerning
+            .get(&(left, right))
+            .map_or(0, |amount| amount * scale as i32)
+    }
+
+    fn draw_glyph(
+        &self,
+        framebuffer: &mut Framebuffer,
+        ch: char,
+        x: i32,
+        y: i32,
+        scale: u32,
+        color: Color,
+    ) {
+        let Some(glyph) = self.glyph(ch) else {
+            return;
+        };
+        if glyph.source.is_empty() {
+            return;
+        }
+
+        let s = scale as i32;
+        let dest = Rect::new(
+            x + glyph.offset_x * s,
+            y + glyph.offset_y * s,
+            glyph.source.w * s,
+            glyph.source.h * s,
+        );
+        let options = BlitOptions {
+            source: Some(glyph.source),
+            tint: color,
+            ..BlitOptions::default()
+        };
+        framebuffer.blit(&self.pages[glyph.page], dest, &options);
+    }
+}
+
+/// `key=value` pairs from one BMFont descriptor line.
+struct BmAttributes<'a> {
+    line: usize,
+    pairs: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> BmAttributes<'a> {
+    fn parse(mut rest: &'a str, line: usize) -> Result<Self, FontError> {
+        let mut pairs = Vec::new();
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+
+            let (key, after_key) =
+                rest.split_once('=')
+                    .ok_or_else(|| FontError::Parse {
+                        line,
+                        message: format!("expected key=value, found {rest:?}"),
+                    })?;
+            let (value, remaining) =
+                if let Some(quoted) = after_key.strip_prefix('"') {
+                    quoted
+                        .split_once('"')
+                        .ok_or_else(|| FontError::Parse {
+                            line,
+                            message: "unterminated string".to_owned(),
+                        })?
+                } else {
+                    after_key
+                        .split_once(' ')
+                        .unwrap_or((after_key, ""))
+                };
+
+            pairs.push((key, value));
+            rest = remaining;
+        }
+
+        Ok(Self { line, pairs })
+    }
+
+    fn string(&self, key: &str) -> Result<&'a str, FontError> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+            .ok_or_else(|| self.error(format!("missing {key}")))
+    }
+
+    fn int(&self, key: &str) -> Result<i32, FontError> {
+        let value = self.string(key)?;
+        value
+            .parse()
+            .map_err(|_| self.error(format!("{key}={value} is not an integer")))
+    }
+
+    fn error(&self, message: String) -> FontError {
+        FontError::Parse { line: self.line, message }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::TextStyle;
+    use crate::text::measure_text;
+
+    const DESCRIPTOR: &str = r#"info face="Test" size=4
+common lineHeight=5 base=4 scaleW=8 scaleH=4 pages=1
+page id=0 file="test.png"
+chars count=2
+char id=65 x=0 y=0 width=3 height=4 xoffset=0 yoffset=1 xadvance=4 page=0
+char id=63 x=4 y=0 width=3 height=4 xoffset=0 yoffset=1 xadvance=4 page=0
+kernings count=1
+kerning first=65 second=65 amount=-1
+"#;
+
+    fn page(file: &str) -> Result<Image, FontError> {
+        assert_eq!(file, "test.png");
+        let mut image = Image::new(8, 4);
+        image.pixels_mut().fill(Color::WHITE);
+        Ok(image)
+    }
+
+    #[test]
+    fn builtin_glyphs_render_expected_bits() {
+        let font = BitmapFont::builtin_8x8();
+        let mut fb = Framebuffer::new(8, 8);
+        font.draw_glyph(&mut fb, '!', 0, 0, 1, Color::WHITE);
+
+        // Row 0 of '!' is 0x18: columns 3 and 4.
+        let row: Vec<_> = (0..8)
+            .map(|x| fb.pixel(x, 0) != Some(0))
+            .collect();
+        assert_eq!(row, [false, false, false, true, true, false, false, false]);
+    }
+
+    #[test]
+    fn tall_builtin_doubles_rows() {
+        let font = BitmapFont::builtin_8x16();
+        let mut fb = Framebuffer::new(8, 16);
+        font.draw_glyph(&mut fb, '_', 0, 0, 1, Color::WHITE);
+
+        assert_eq!(Font::line_height(&font, 1), 16);
+        assert_eq!(fb.pixel(0, 13), Some(0));
+        assert_eq!(fb.pixel(0, 14), Some(0x00FFFFFF));
+        assert_eq!(fb.pixel(7, 15), Some(0x00FFFFFF));
+    }
+
+    #[test]
+    fn unknown_chars_use_fallback() {
+        let font = BitmapFont::builtin_8x8();
+
+        assert_eq!(font.glyph('é'), font.glyph('?'));
+    }
+
+    #[test]
+    fn parses_bmfont_descriptor() {
+        let font = BitmapFont::parse_bmfont(DESCRIPTOR, page).unwrap();
+
+        assert_eq!(font.line_height(), 5);
+        assert_eq!(font.glyph('A').unwrap().source, Rect::new(0, 0, 3, 4));
+        assert_eq!(font.glyph('Z'), font.glyph('?'));
+        assert_eq!(Font::kerning(&font, 'A', 'A', 2), -2);
+        assert_eq!(measure_text(&font, "AAA", &TextStyle::default()), (10, 5));
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let bad = "common lineHeight=five\n";
+
+        let err = BitmapFont::parse_bmfont(bad, page).unwrap_err();
+
+        assert!(
+            matches!(err, FontError::Parse { line: 1, .. }),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_page_ids() {
+        for id in ["-1", "1000000000"] {
+            let descriptor = format!("page id={id} file=\"a.png\"\n");
+
+            let err = BitmapFont::parse_bmfont(&descriptor, page).unwrap_err();
+
+            assert!(
+                matches!(err, FontError::Parse { line: 1, .. }),
+                "unexpected error: {err}"
+            );
+        }
+    }
+}
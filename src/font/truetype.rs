@@ -0,0 +1,344 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use ab_glyph::{
+    Font as _, FontArc, GlyphId, OutlinedGlyph, PxScale, ScaleFont, point,
+};
+
+use crate::blit::BlitOptions;
+use crate::color::Color;
+use crate::font::FontError;
+use crate::framebuffer::Framebuffer;
+use crate::image::Image;
+use crate::rect::Rect;
+use crate::text::Font;
+
+/// Gap left around each glyph in the atlas so neighbours never bleed.
+const ATLAS_PADDING: i32 = 1;
+
+static NEXT_FONT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Identifies a loaded font within a [`GlyphCache`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FontId(u32);
+
+/// Scalable TrueType/OpenType font.
+///
+/// Draw it through [`TrueTypeFont::sized`], which rasterizes glyphs on
+/// demand into a shared [`GlyphCache`].
+#[derive(Clone, Debug)]
+pub struct TrueTypeFont {
+    id: FontId,
+    font: FontArc,
+}
+
+impl TrueTypeFont {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FontError> {
+        Self::from_bytes(fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, FontError> {
+        let font = FontArc::try_from_vec(bytes)
+            .map_err(|err| FontError::Invalid(err.to_string()))?;
+        let id = FontId(NEXT_FONT_ID.fetch_add(1, Ordering::Relaxed));
+
+        Ok(Self { id, font })
+    }
+
+    pub fn id(&self) -> FontId {
+        self.id
+    }
+
+    /// This font at `size_px` pixels tall, caching glyphs in `cache`.
+    pub fn sized<'a>(
+        &'a self,
+        size_px: f32,
+        cache: &'a RefCell<GlyphCache>,
+    ) -> SizedFont<'a> {
+        SizedFont { font: self, size_px, cache }
+    }
+}
+
+/// A [`TrueTypeFont`] at a particular pixel size, usable with
+/// [`Framebuffer::draw_text`]. The text style's `scale` multiplies the
+/// size, so text stays crisp rather than being pixel-doubled.
+#[derive(Clone, Copy, Debug)]
+pub struct SizedFont<'a> {
+    font: &'a TrueTypeFont,
+    size_px: f32,
+    cache: &'a RefCell<GlyphCache>,
+}
+
+impl SizedFont<'_> {
+    fn scale(&self, scale: u32) -> PxScale {
+        PxScale::from(self.size_px * scale as f32)
+    }
+
+    fn glyph_id(&self, ch: char) -> GlyphId {
+        self.font.font.glyph_id(ch)
+    }
+}
+
+impl Font for SizedFont<'_> {
+    fn line_height(&self, scale: u32) -> i32 {
+        let font = self
+            .font
+            .font
+            .as_scaled(self.scale(scale));
+        (font.height() + font.line_gap()).ceil() as i32
+    }
+
+    fn advance(&self, ch: char, scale: u32) -> i32 {
+        let font = self
+            .font
+            .font
+            .as_scaled(self.scale(scale));
+        font.h_advance(self.glyph_id(ch))
+            .round() as i32
+    }
+
+    fn kerning(&self, left: char, right: char, scale: u32) -> i32 {
+        let font = self
+            .font
+            .font
+            .as_scaled(self.scale(scale));
+        font.kern(self.glyph_id(left), self.glyph_id(right))
+            .round() as i32
+    }
+
+    fn draw_glyph(
+        &self,
+        framebuffer: &mut Framebuffer,
+        ch: char,
+        x: i32,
+        y: i32,
+        scale: u32,
+        color: Color,
+    ) {
+        let mut cache = self.cache.borrow_mut();
+        let Some(bitmap) =
+            cache.glyph(self.font, self.glyph_id(ch), self.scale(scale))
+        else {
+            return;
+        };
+        let uncached;
+        let (image, glyph) = match bitmap {
+            GlyphBitmap::Atlas(glyph) => (&cache.atlas, glyph),
+            GlyphBitmap::Uncached(image, glyph) => {
+                uncached = image;
+                (&uncached, glyph)
+            }
+        };
+
+        let dest = Rect::new(
+            x + glyph.offset_x,
+            y + glyph.offset_y,
+            glyph.source.w,
+            glyph.source.h,
+        );
+        let options = BlitOptions {
+            source: Some(glyph.source),
+            tint: color,
+            ..BlitOptions::default()
+        };
+        framebuffer.blit(image, dest, &options);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: FontId,
+    /// Pixel size in 1/64ths, so nearby float sizes share entries.
+    size: u32,
+    glyph: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CachedGlyph {
+    source: Rect,
+    /// Offset from the top-left of the line box to the bitmap.
+    offset_x: i32,
+    offset_y: i32,
+}
+
+impl CachedGlyph {
+    /// Cached for glyphs that have nothing to draw.
+    const EMPTY: Self = Self {
+        source: Rect::new(0, 0, 0, 0),
+        offset_x: 0,
+        offset_y: 0,
+    };
+}
+
+/// Where a glyph's coverage was rasterized.
+enum GlyphBitmap {
+    Atlas(CachedGlyph),
+    /// Too big for the atlas, so rasterized for this draw alone.
+    Uncached(Image, CachedGlyph),
+}
+
+/// Atlas of antialiased glyph coverage keyed by (font, size, glyph).
+///
+/// Glyphs are packed into shelves; when the atlas fills up it is cleared
+/// and refilled with whatever is drawn next. Glyphs bigger than the
+/// whole atlas are rasterized again on every draw.
+#[derive(Debug)]
+pub struct GlyphCache {
+    atlas: Image,
+    entries: HashMap<GlyphKey, CachedGlyph>,
+    shelf_x: i32,
+    shelf_y: i32,
+    shelf_height: i32,
+}
+
+impl GlyphCache {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            atlas: Image::new(width, height),
+            entries: HashMap::new(),
+            shelf_x: 0,
+            shelf_y: 0,
+            shelf_height: 0,
+        }
+    }
+
+    /// White glyph coverage stored in the alpha channel.
+    pub fn atlas(&self) -> &Image {
+        &self.atlas
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.atlas
+            .pixels_mut()
+            .fill(Color::TRANSPARENT);
+        (self.shelf_x, self.shelf_y, self.shelf_height) = (0, 0, 0);
+    }
+
+    /// Looks up a glyph, rasterizing it on a miss. Returns `None` for
+    /// glyphs with no outline, like spaces.
+    fn glyph(
+        &mut self,
+        font: &TrueTypeFont,
+        glyph: GlyphId,
+        scale: PxScale,
+    ) -> Option<GlyphBitmap> {
+        let key = GlyphKey {
+            font: font.id,
+            size: (scale.y * 64.0).round() as u32,
+            glyph: glyph.0,
+        };
+        if let Some(cached) = self.entries.get(&key) {
+            return (!cached.source.is_empty())
+                .then_some(GlyphBitmap::Atlas(*cached));
+        }
+
+        let ascent = font
+            .font
+            .as_scaled(scale)
+            .ascent()
+            .round();
+        let outline = font.font.outline_glyph(
+            glyph.with_scale_and_position(scale, point(0.0, 0.0)),
+        );
+        let Some(outline) = outline else {
+            self.entries
+                .insert(key, CachedGlyph::EMPTY);
+            return None;
+        };
+
+        let bounds = outline.px_bounds();
+        let (w, h) = (bounds.width() as i32, bounds.height() as i32);
+        let (offset_x, offset_y) =
+            (bounds.min.x as i32, (ascent + bounds.min.y) as i32);
+        // Clearing wouldn't make room, so don't evict everything else.
+        if w > self.atlas.width() as i32 || h > self.atlas.height() as i32 {
+            let mut image = Image::new(w as usize, h as usize);
+            draw_coverage(&outline, &mut image, 0, 0);
+            let source = Rect::new(0, 0, w, h);
+            let glyph = CachedGlyph { source, offset_x, offset_y };
+            return Some(GlyphBitmap::Uncached(image, glyph));
+        }
+        let source = match self.allocate(w, h) {
+            Some(source) => source,
+            None => {
+                self.clear();
+                self.allocate(w, h)?
+            }
+        };
+        draw_coverage(&outline, &mut self.atlas, source.x, source.y);
+
+        let cached = CachedGlyph { source, offset_x, offset_y };
+        self.entries.insert(key, cached);
+        Some(GlyphBitmap::Atlas(cached))
+    }
+
+    /// Reserves a `w`x`h` region using shelf packing.
+    fn allocate(&mut self, w: i32, h: i32) -> Option<Rect> {
+        let (atlas_w, atlas_h) =
+            (self.atlas.width() as i32, self.atlas.height() as i32);
+        if w > atlas_w || h > atlas_h {
+            return None;
+        }
+
+        if self.shelf_x + w > atlas_w {
+            self.shelf_y += self.shelf_height + ATLAS_PADDING;
+            (self.shelf_x, self.shelf_height) = (0, 0);
+        }
+        if self.shelf_y + h > atlas_h {
+            return None;
+        }
+
+        let rect = Rect::new(self.shelf_x, self.shelf_y, w, h);
+        self.shelf_x += w + ATLAS_PADDING;
+        self.shelf_height = self.shelf_height.max(h);
+        Some(rect)
+    }
+}
+
+/// Writes `outline`'s coverage as white alpha with its top-left at
+/// `(x, y)` in `image`.
+fn draw_coverage(outline: &OutlinedGlyph, image: &mut Image, x: i32, y: i32) {
+    outline.draw(|gx, gy, coverage| {
+        let alpha = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+        image.set_pixel(
+            (x + gx as i32) as usize,
+            (y + gy as i32) as usize,
+            Color::WHITE.with_alpha(alpha),
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shelf_packing_wraps_rows_and_reports_full() {
+        let mut cache = GlyphCache::new(10, 10);
+
+        assert_eq!(cache.allocate(4, 3), Some(Rect::new(0, 0, 4, 3)));
+        assert_eq!(cache.allocate(4, 5), Some(Rect::new(5, 0, 4, 5)));
+        assert_eq!(cache.allocate(4, 2), Some(Rect::new(0, 6, 4, 2)));
+        assert_eq!(cache.allocate(11, 1), None);
+        assert_eq!(cache.allocate(4, 5), None);
+    }
+
+    #[test]
+    fn rejects_invalid_font_data() {
+        let err = TrueTypeFont::from_bytes(b"not a font".to_vec()).unwrap_err();
+
+        assert!(matches!(err, FontError::Invalid(_)));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::color::{BlendMode, Color};
+
+/// CPU-side pixel buffer that all drawing goes through.
+///
+/// Pixels are stored row-major in softbuffer's `0RGB` layout so a presenter
+/// can copy them to a window surface unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<u32>,
+    blend_mode: BlendMode,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width * height],
+            blend_mode: BlendMode::default(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u32] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [u32] {
+        &mut self.pixels
+    }
+
+    /// Resizes the buffer, discarding its contents if the size changed.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if self.width == width && self.height == height {
+            return;
+        }
+
+        self.width = width;
+        self.height = height;
+        self.pixels.clear();
+        self.pixels.resize(width * height, 0);
+    }
+
+    /// Blend mode applied by every draw call.
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    /// Overwrites every pixel, ignoring alpha and the blend mode.
+    pub fn fill(&mut self, color: Color) {
+        self.pixels.fill(color.to_0rgb());
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Option<u32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(self.pixels[y * self.width + x])
+    }
+
+    /// Writes a raw `0RGB` pixel, ignoring coordinates outside the buffer.
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: u32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        self.pixels[y * self.width + x] = color;
+    }
+}
+
+impl Framebuffer {
+    /// Blends `color` into a pixel using the current blend mode.
+    pub fn blend_pixel(&mut self, x: usize, y: usize, color: Color) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let pixel = &mut self.pixels[y * self.width + x];
+        *pixel = self.blend_mode.blend(color, *pixel);
+    }
+
+    /// Blends `color` into a run of pixels on one row, which must be in
+    /// bounds.
+    pub(crate) fn blend_span(
+        &mut self,
+        row_start: usize,
+        len: usize,
+        color: Color,
+    ) {
+        let span = &mut self.pixels[row_start..row_start + len];
+        let mode = self.blend_mode;
+        if mode == BlendMode::Replace
+            || (mode == BlendMode::SourceOver && color.a == 255)
+        {
+            span.fill(color.to_0rgb());
+            return;
+        }
+
+        for pixel in span {
+            *pixel = mode.blend(color, *pixel);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Golden-image comparisons for rendered frames.
+//!
+//! References live in `tests/golden/<name>.ppm`. Run the tests with
+//! `UPDATE_GOLDEN=1` to (re)write them from the current output. On a
+//! mismatch the actual frame and a diff image are written to
+//! `target/golden/` next to the failing test's name.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::framebuffer::Framebuffer;
+use crate::ppm;
+
+const DIFF_COLOR: u32 = 0x00FF0000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tolerance {
+    /// Largest per-channel difference that still counts as a match.
+    pub channel: u8,
+    /// Number of mismatching pixels allowed before the comparison fails.
+    pub pixels: usize,
+}
+
+impl Tolerance {
+    pub const EXACT: Self = Self { channel: 0, pixels: 0 };
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self { channel: 2, pixels: 0 }
+    }
+}
+
+#[derive(Debug)]
+pub struct Comparison {
+    pub mismatched: usize,
+    pub max_channel_diff: u8,
+    /// Expected image dimmed, with mismatching pixels highlighted in red.
+    pub diff: Framebuffer,
+}
+
+impl Comparison {
+    pub fn passes(&self, tolerance: Tolerance) -> bool {
+        self.mismatched <= tolerance.pixels
+    }
+}
+
+/// Compares two frames pixel by pixel.
+///
+/// Returns `None` when the dimensions differ.
+pub fn compare(
+    actual: &Framebuffer,
+    expected: &Framebuffer,
+    tolerance: Tolerance,
+) -> Option<Comparison> {
+    if actual.width() != expected.width()
+        || actual.height() != expected.height()
+    {
+        return None;
+    }
+
+    let mut diff = Framebuffer::new(expected.width(), expected.height());
+    let mut mismatched = 0;
+    let mut max_channel_diff = 0;
+
+    for ((out, &a), &e) in diff
+        .pixels_mut()
+        .iter_mut()
+        .zip(actual.pixels())
+        .zip(expected.pixels())
+    {
+        let channel_diff = (0..3)
+            .map(|i| {
+                let shift = i * 8;
+                ((a >> shift) as u8).abs_diff((e >> shift) as u8)
+            })
+            .max()
+            .unwrap_or(0);
+        max_channel_diff = max_channel_diff.max(channel_diff);
+
+        if channel_diff > tolerance.channel {
+            mismatched += 1;
+            *out = DIFF_COLOR;
+        } else {
+            *out = (e >> 2) & 0x003F3F3F;
+        }
+    }
+
+    Some(Comparison { mismatched, max_channel_diff, diff })
+}
+
+/// Asserts that `actual` matches the stored reference named `name`.
+pub fn assert_golden(name: &str, actual: &Framebuffer, tolerance: Tolerance) {
+    let reference_path = golden_dir().join(format!("{name}.ppm"));
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(golden_dir())
+            .expect("failed to create golden directory");
+        ppm::save(&reference_path, actual)
+            .expect("failed to write golden image");
+        return;
+    }
+
+    let expected = ppm::load(&reference_path).unwrap_or_else(|err| {
+        panic!(
+            "failed to load golden image {}: {err} \
+             (run with UPDATE_GOLDEN=1 to create it)",
+            reference_path.display()
+        )
+    });
+
+    let comparison = compare(actual, &expected, tolerance);
+    if comparison
+        .as_ref()
+        .is_some_and(|c| c.passes(tolerance))
+    {
+        return;
+    }
+
+    fs::create_dir_all(output_dir()).expect("failed to create output dir");
+    let actual_path = output_dir().join(format!("{name}.actual.ppm"));
+    ppm::save(&actual_path, actual).expect("failed to write actual image");
+
+    match comparison {
+        Some(comparison) => {
+            let diff_path = output_dir().join(format!("{name}.diff.ppm"));
+            ppm::save(&diff_path, &comparison.diff)
+                .expect("failed to write diff image");
+            panic!(
+                "golden image {name} mismatch: {} pixels differ \
+                 (max channel diff {}, tolerance {:?}); \
+                 actual: {}, diff: {}",
+                comparison.mismatched,
+                comparison.max_channel_diff,
+                tolerance,
+                actual_path.display(),
+                diff_path.display()
+            );
+        }
+        None => panic!(
+            "golden image {name} size mismatch: expected {}x{}, got {}x{}; \
+             actual: {}",
+            expected.width(),
+            expected.height(),
+            actual.width(),
+            actual.height(),
+            actual_path.display()
+        ),
+    }
+}
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn output_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/golden")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    #[test]
+    fn identical_frames_match_exactly() {
+        let mut frame = Framebuffer::new(4, 4);
+        frame.fill(Color::rgb(0x12, 0x34, 0x56));
+
+        let comparison = compare(&frame, &frame, Tolerance::EXACT).unwrap();
+
+        assert_eq!(comparison.mismatched, 0);
+        assert!(comparison.passes(Tolerance::EXACT));
+    }
+
+    #[test]
+    fn small_channel_differences_are_tolerated() {
+        let expected = Framebuffer::new(2, 1);
+        let mut actual = expected.clone();
+        actual.set_pixel(0, 0, 0x00010201);
+        actual.set_pixel(1, 0, 0x00000010);
+
+        let comparison =
+            compare(&actual, &expected, Tolerance::default()).unwrap();
+
+        assert_eq!(comparison.mismatched, 1);
+        assert_eq!(comparison.max_channel_diff, 0x10);
+        assert_eq!(comparison.diff.pixel(1, 0), Some(DIFF_COLOR));
+        assert_ne!(comparison.diff.pixel(0, 0), Some(DIFF_COLOR));
+    }
+
+    #[test]
+    fn size_mismatch_is_reported() {
+        let a = Framebuffer::new(2, 2);
+        let b = Framebuffer::new(2, 3);
+
+        assert!(compare(&a, &b, Tolerance::EXACT).is_none());
+    }
+}
@@ -0,0 +1,521 @@
+use std::cell::{Ref, RefMut};
+use std::path::Path;
+
+use crate::assets::{AssetEvent, Assets, Handle};
+use crate::blit::BlitOptions;
+use crate::camera::Camera2D;
+use crate::canvas::VirtualCanvas;
+use crate::collision::Shape;
+use crate::color::Color;
+use crate::components::{
+    Bounds, Collider, Drawable, Fill, Label, PlayerControlled, Position,
+    PreviousPosition, Rotation, Sprite, Velocity,
+};
+use crate::dpi::WindowMetrics;
+use crate::ecs::{Entity, Schedule, World};
+use crate::font::BitmapFont;
+use crate::framebuffer::Framebuffer;
+use crate::hierarchy::{root_transform, world_transforms};
+use crate::image::Image;
+use crate::input::{InputEvent, InputMap, InputState};
+use crate::math::Vec2;
+use crate::physics::PhysicsSettings;
+use crate::rect::Rect;
+use crate::scene::{Scene, SceneError};
+use crate::systems::{self, Arena, Time};
+use crate::text::TextStyle;
+use crate::transform::Transform2D;
+
+const BACKGROUND_COLOR: Color = Color::rgb(0x20, 0x20, 0x20);
+const SQUARE_COLOR: Color = Color::MAGENTA;
+/// Pixels per second the square moves while a movement key is held.
+const PLAYER_SPEED: f64 = 300.0;
+
+pub struct GraphicsState {
+    pub framebuffer: Framebuffer,
+    pub world: World,
+    /// Systems run once per fixed tick.
+    pub schedule: Schedule,
+    /// The player-controlled bouncing square.
+    pub square: Entity,
+    /// Drawn stretched over the square instead of a solid fill when set.
+    pub square_sprite: Option<Handle<Image>>,
+    /// Fixed resolution `framebuffer` stays at, when set.
+    canvas: Option<VirtualCanvas>,
+    /// Draws [`Label`]s.
+    font: BitmapFont,
+    window: WindowMetrics,
+}
+
+impl GraphicsState {
+    /// A `width` x `height` frame showing a world of the same size.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_arena(width, height, width.max(1), height.max(1))
+    }
+
+    /// A `width` x `height` frame onto an `arena_width` x `arena_height`
+    /// world, with the camera centered on the arena at zoom 1.
+    pub fn with_arena(
+        width: usize,
+        height: usize,
+        arena_width: usize,
+        arena_height: usize,
+    ) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        let arena_width = arena_width.max(1);
+        let arena_height = arena_height.max(1);
+
+        let (mw, mh) = square_size(arena_width, arena_height);
+        let square_pos_x = (arena_width / 2 - mw / 2) as f64;
+        let square_pos_y = (arena_height / 2 - mh / 2) as f64;
+
+        let mut world = World::new();
+        world.insert_resource(Time::default());
+        world.insert_resource(Arena {
+            width: arena_width as f64,
+            height: arena_height as f64,
+        });
+        world.insert_resource(
+            Camera2D::new(width as f64, height as f64).with_position(
+                Vec2::new(arena_width as f64, arena_height as f64) / 2.0,
+            ),
+        );
+        world.insert_resource(InputState::new());
+        world.insert_resource(InputMap::builtin());
+        world.insert_resource(PhysicsSettings::default());
+        world.insert_resource(Assets::new());
+        let square = world
+            .spawn()
+            .with(Position { x: square_pos_x, y: square_pos_y })
+            .with(PreviousPosition { x: square_pos_x, y: square_pos_y })
+            .with(Velocity { x: 100.0, y: 100.0 })
+            .with(Bounds { w: mw as f64, h: mh as f64 })
+            .with(PlayerControlled { speed: PLAYER_SPEED })
+            .id();
+
+        Self {
+            framebuffer: Framebuffer::new(width, height),
+            world,
+            schedule: systems::motion_schedule(),
+            square,
+            square_sprite: None,
+            canvas: None,
+            font: BitmapFont::builtin_8x8(),
+            window: WindowMetrics::new(width, height, 1.0),
+        }
+    }
+
+    /// Renders at the canvas resolution, over an arena of the same size,
+    /// for a `window_width` x `window_height` window. Present the
+    /// framebuffer with [`VirtualCanvas::present`].
+    pub fn with_canvas(
+        canvas: VirtualCanvas,
+        window_width: usize,
+        window_height: usize,
+    ) -> Self {
+        let mut state = Self::new(canvas.width, canvas.height);
+        state.canvas = Some(canvas);
+        state.window.physical_size =
+            (window_width.max(1), window_height.max(1));
+        state
+    }
+
+    /// A frame filling a `width` x `height` physical pixel window, onto an
+    /// arena of the window's logical size so the world looks the same at
+    /// any scale factor.
+    pub fn for_window(width: usize, height: usize, scale_factor: f64) -> Self {
+        let metrics = WindowMetrics::new(width, height, scale_factor);
+        let (arena_width, arena_height) = metrics.logical_size();
+        let mut state = Self::with_arena(
+            width,
+            height,
+            arena_width.round() as usize,
+            arena_height.round() as usize,
+        );
+        state.set_scale_factor(metrics.scale_factor);
+        state
+    }
+
+    pub fn canvas(&self) -> Option<VirtualCanvas> {
+        self.canvas
+    }
+
+    /// Size of the window being drawn to, in physical pixels.
+    pub fn window_size(&self) -> (usize, usize) {
+        self.window.physical_size
+    }
+
+    pub fn window_metrics(&self) -> WindowMetrics {
+        self.window
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.window.scale_factor
+    }
+
+    /// Follows the window moving to a display with a different scale
+    /// factor. Without a canvas the camera zooms along, so world units
+    /// stay the same number of logical pixels.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        let (width, height) = self.window.physical_size;
+        let old = self.window.scale_factor;
+        self.window = WindowMetrics::new(width, height, scale_factor);
+        if self.canvas.is_none() {
+            self.camera_mut().zoom *= self.window.scale_factor / old;
+        }
+    }
+
+    /// Follows the window resizing to `width` x `height`. Without a
+    /// canvas this resizes the frame and the camera's viewport; the arena
+    /// and everything in it keep their world size either way.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let (width, height) = (width.max(1), height.max(1));
+        self.window.physical_size = (width, height);
+        if self.canvas.is_some() {
+            return;
+        }
+        self.framebuffer.resize(width, height);
+        self.camera_mut().viewport = Vec2::new(width as f64, height as f64);
+    }
+
+    /// Advances the simulation by one fixed tick of `dt` seconds.
+    pub fn update(&mut self, dt: f64) {
+        *self
+            .world
+            .resource_mut::<Time>()
+            .expect("time is inserted in new") = Time { dt };
+        self.schedule.run(&mut self.world);
+        self.input_mut().end_tick();
+    }
+
+    /// Applies window input for the next tick, following resizes and
+    /// scale factor changes and mapping the cursor onto the canvas.
+    pub fn apply_input(&mut self, event: &InputEvent) {
+        match (*event, self.canvas) {
+            (InputEvent::Resized { width, height }, _) => {
+                self.resize(width as usize, height as usize)
+            }
+            (InputEvent::ScaleFactorChanged { scale_factor }, _) => {
+                self.set_scale_factor(scale_factor)
+            }
+            (InputEvent::CursorMoved { x, y }, Some(canvas)) => {
+                let (x, y) =
+                    canvas.window_to_canvas(self.window.physical_size, (x, y));
+                self.input_mut().set_cursor(x, y);
+            }
+            _ => self.input_mut().apply(event),
+        }
+    }
+
+    pub fn input(&self) -> Ref<'_, InputState> {
+        self.world
+            .resource()
+            .expect("input state is inserted in new")
+    }
+
+    /// Input read by the next tick's systems.
+    pub fn input_mut(&self) -> RefMut<'_, InputState> {
+        self.world
+            .resource_mut()
+            .expect("input state is inserted in new")
+    }
+
+    pub fn input_map(&self) -> Ref<'_, InputMap> {
+        self.world
+            .resource()
+            .expect("input map is inserted in new")
+    }
+
+    pub fn set_input_map(&mut self, map: InputMap) {
+        self.world.insert_resource(map);
+    }
+
+    pub fn assets(&self) -> Ref<'_, Assets> {
+        self.world
+            .resource()
+            .expect("assets are inserted in new")
+    }
+
+    pub fn assets_mut(&self) -> RefMut<'_, Assets> {
+        self.world
+            .resource_mut()
+            .expect("assets are inserted in new")
+    }
+
+    /// Receives finished background loads and frees unused assets; see
+    /// [`Assets::update`].
+    pub fn update_assets(&mut self) -> Vec<AssetEvent> {
+        self.assets_mut().update()
+    }
+
+    /// Reloads every asset loaded from `path`, e.g. after an
+    /// [`AssetWatcher`] saw it change. Sprites pick up new images by
+    /// themselves; reacting to other reloads is up to the caller.
+    ///
+    /// [`AssetWatcher`]: crate::assets::AssetWatcher
+    pub fn reload_asset(&mut self, path: impl AsRef<Path>) -> Vec<AssetEvent> {
+        self.assets_mut().reload(path)
+    }
+
+    /// Replaces every entity with `scene`'s, loading sprites relative to
+    /// `base_dir`. The first player-controlled entity becomes the square.
+    /// On error the current entities are kept.
+    pub fn load_scene(
+        &mut self,
+        scene: &Scene,
+        base_dir: impl AsRef<Path>,
+    ) -> Result<(), SceneError> {
+        let old: Vec<Entity> = self.world.entities().collect();
+        let spawned = scene.spawn(&mut self.world, base_dir)?;
+        for entity in old {
+            self.world.despawn(entity);
+        }
+        if let Some(&player) = spawned.iter().find(|&&entity| {
+            self.world
+                .get::<PlayerControlled>(entity)
+                .is_some()
+        }) {
+            self.square = player;
+        }
+        Ok(())
+    }
+
+    pub fn camera(&self) -> Ref<'_, Camera2D> {
+        self.world
+            .resource()
+            .expect("camera is inserted in new")
+    }
+
+    pub fn camera_mut(&self) -> RefMut<'_, Camera2D> {
+        self.world
+            .resource_mut()
+            .expect("camera is inserted in new")
+    }
+
+    /// Draws every entity with [`Position`] and [`Bounds`] through the
+    /// [`Camera2D`], interpolated `alpha` of the way from its
+    /// [`PreviousPosition`] to the current one. Entities with a
+    /// [`Collider`] but no [`Bounds`] are drawn as their collision shape.
+    /// Sprites stay upright under a rotated camera.
+    pub fn render(&mut self, alpha: f64) {
+        self.framebuffer.fill(BACKGROUND_COLOR);
+        let camera = *self.camera();
+        let assets = self
+            .world
+            .resource::<Assets>()
+            .expect("assets are inserted in new");
+
+        self.world.query::<(
+            &Position,
+            Option<&PreviousPosition>,
+            &Bounds,
+            Option<&Fill>,
+            Option<&Sprite>,
+        )>(|entity, (pos, prev, bounds, fill, sprite)| {
+            let top_left = interpolate(pos, prev, alpha);
+            let color = fill.map_or(SQUARE_COLOR, |fill| fill.0);
+            let corners = [
+                top_left,
+                top_left + Vec2::new(bounds.w, 0.0),
+                top_left + Vec2::new(bounds.w, bounds.h),
+                top_left + Vec2::new(0.0, bounds.h),
+            ]
+            .map(|corner| camera.world_to_screen(corner));
+            let rect = if camera.rotation == 0.0 {
+                let size = Vec2::new(bounds.w, bounds.h) * camera.zoom;
+                Rect::new(
+                    corners[0].x as i32,
+                    corners[0].y as i32,
+                    size.x as i32,
+                    size.y as i32,
+                )
+            } else {
+                let min = corners
+                    .iter()
+                    .fold(corners[0], |a, &b| a.min(b));
+                let max = corners
+                    .iter()
+                    .fold(corners[0], |a, &b| a.max(b));
+                let size = max - min;
+                Rect::new(
+                    min.x as i32,
+                    min.y as i32,
+                    size.x as i32,
+                    size.y as i32,
+                )
+            };
+
+            let sprite = match (sprite, &self.square_sprite, fill) {
+                (Some(sprite), ..) => assets.get(&sprite.image),
+                (None, Some(sprite), None) if entity == self.square => {
+                    assets.get(sprite)
+                }
+                _ => None,
+            };
+            match sprite {
+                Some(sprite) => {
+                    let options = BlitOptions::default();
+                    self.framebuffer
+                        .blit(sprite, rect, &options);
+                }
+                _ if camera.rotation != 0.0 => {
+                    let corners = corners.map(|c| (c.x, c.y));
+                    self.framebuffer
+                        .fill_polygon(&corners, color);
+                }
+                _ => {
+                    self.framebuffer
+                        .fill_rect(rect.x, rect.y, rect.w, rect.h, color);
+                }
+            }
+        });
+        drop(assets);
+
+        self.world.query::<(
+            &Position,
+            Option<&PreviousPosition>,
+            &Collider,
+            Option<&Rotation>,
+            Option<&Fill>,
+            Option<&Bounds>,
+        )>(
+            |_, (pos, prev, collider, rotation, fill, bounds)| {
+                if bounds.is_some() {
+                    return;
+                }
+                let center = interpolate(pos, prev, alpha);
+                let rotation = rotation.map_or(0.0, |rotation| rotation.0);
+                let color = fill.map_or(SQUARE_COLOR, |fill| fill.0);
+                let shape = collider.0.transformed(center, rotation);
+                fill_shape(&mut self.framebuffer, &camera, &shape, color);
+            },
+        );
+
+        self.render_hierarchy(&camera, alpha);
+    }
+
+    /// Draws children at their parents' interpolated placement, so they
+    /// move smoothly with them.
+    fn render_hierarchy(&mut self, camera: &Camera2D, alpha: f64) {
+        let world = &self.world;
+        let transforms = world_transforms(world, |entity| {
+            let transform = root_transform(world, entity);
+            match world.get::<Position>(entity) {
+                Some(pos) => {
+                    let prev = world.get::<PreviousPosition>(entity);
+                    Transform2D {
+                        translation: interpolate(&pos, prev.as_deref(), alpha),
+                        ..transform
+                    }
+                }
+                None => transform,
+            }
+        });
+        let mut entities: Vec<_> = transforms.keys().copied().collect();
+        entities.sort();
+
+        for entity in entities {
+            let transform = transforms[&entity];
+            if let Some(drawable) = world.get::<Drawable>(entity) {
+                let color = world
+                    .get::<Fill>(entity)
+                    .map_or(SQUARE_COLOR, |fill| fill.0);
+                let shape = transform.transform_shape(&drawable.0);
+                fill_shape(&mut self.framebuffer, camera, &shape, color);
+            }
+            if let Some(label) = world.get::<Label>(entity) {
+                let origin = camera.world_to_screen(transform.translation);
+                let scale = (transform.scale.y.abs() * camera.zoom).round();
+                let style = TextStyle {
+                    color: label.color,
+                    scale: (scale as u32).max(1),
+                    ..TextStyle::default()
+                };
+                self.framebuffer.draw_text(
+                    &self.font,
+                    &label.text,
+                    origin.x.round() as i32,
+                    origin.y.round() as i32,
+                    &style,
+                );
+            }
+        }
+    }
+
+    pub fn square_position(&self) -> Position {
+        self.world
+            .get::<Position>(self.square)
+            .map_or(Position::default(), |pos| *pos)
+    }
+
+    pub fn square_velocity(&self) -> Velocity {
+        self.world
+            .get::<Velocity>(self.square)
+            .map_or(Velocity::default(), |vel| *vel)
+    }
+
+    /// Moves the square without interpolating from its old position.
+    pub fn teleport_square(&mut self, x: f64, y: f64) {
+        self.world
+            .insert(self.square, Position { x, y });
+        self.world
+            .insert(self.square, PreviousPosition { x, y });
+    }
+
+    /// Furthest the square's top-left corner can go inside the arena.
+    pub fn max_square_pos(&self) -> (f64, f64) {
+        let arena = self
+            .world
+            .resource::<Arena>()
+            .map_or(Arena::default(), |arena| *arena);
+        let bounds = self
+            .world
+            .get::<Bounds>(self.square)
+            .map_or(Bounds::default(), |bounds| *bounds);
+
+        ((arena.width - bounds.w).max(0.0), (arena.height - bounds.h).max(0.0))
+    }
+}
+
+/// The square covers 10% of the arena on each axis.
+fn square_size(width: usize, height: usize) -> (usize, usize) {
+    (width * 10 / 100, height * 10 / 100)
+}
+
+/// World position `alpha` of the way from the previous tick's.
+fn interpolate(
+    pos: &Position,
+    prev: Option<&PreviousPosition>,
+    alpha: f64,
+) -> Vec2 {
+    let pos = Vec2::new(pos.x, pos.y);
+    match prev {
+        Some(prev) => Vec2::new(prev.x, prev.y).lerp(pos, alpha),
+        None => pos,
+    }
+}
+
+/// Fills `shape`, given in world units, through `camera`.
+fn fill_shape(
+    framebuffer: &mut Framebuffer,
+    camera: &Camera2D,
+    shape: &Shape,
+    color: Color,
+) {
+    if let Some(obb) = shape.as_obb() {
+        let corners = obb.corners().map(|corner| {
+            let corner = camera.world_to_screen(corner);
+            (corner.x, corner.y)
+        });
+        framebuffer.fill_polygon(&corners, color);
+    } else if let Shape::Circle(circle) = shape {
+        let center = camera.world_to_screen(circle.center);
+        framebuffer.fill_circle(
+            center.x.round() as i32,
+            center.y.round() as i32,
+            (circle.radius * camera.zoom).round() as i32,
+            color,
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Parent/child relationships between entities and the world transforms
+//! they compose into.
+
+use std::collections::HashMap;
+
+use crate::components::{LocalTransform, Parent, Position, Rotation};
+use crate::ecs::{Entity, World};
+use crate::math::Vec2;
+use crate::transform::Transform2D;
+
+/// World transform of every entity with a [`LocalTransform`], each
+/// composed onto its parent's. Parents outside the hierarchy are placed
+/// by `root`; children of despawned parents, and every entity in a
+/// parent cycle, are treated as roots.
+pub fn world_transforms(
+    world: &World,
+    root: impl Fn(Entity) -> Transform2D,
+) -> HashMap<Entity, Transform2D> {
+    let mut nodes = HashMap::new();
+    world.query::<(&LocalTransform, Option<&Parent>)>(
+        |entity, (local, parent)| {
+            let parent = parent
+                .map(|parent| parent.0)
+                .filter(|&parent| world.is_alive(parent));
+            nodes.insert(entity, (local.0, parent));
+        },
+    );
+
+    let mut resolver = Resolver {
+        nodes: &nodes,
+        root: &root,
+        resolved: HashMap::with_capacity(nodes.len()),
+        path: Vec::new(),
+    };
+    for &entity in nodes.keys() {
+        resolver.resolve(entity);
+    }
+    resolver.resolved
+}
+
+/// Placement of an entity outside the hierarchy from its [`Position`]
+/// and [`Rotation`].
+pub fn root_transform(world: &World, entity: Entity) -> Transform2D {
+    let translation = world
+        .get::<Position>(entity)
+        .map_or(Vec2::ZERO, |pos| Vec2::new(pos.x, pos.y));
+    let rotation = world
+        .get::<Rotation>(entity)
+        .map_or(0.0, |rotation| rotation.0);
+    Transform2D::from_translation(translation).with_rotation(rotation)
+}
+
+/// Entities whose [`Parent`] is `parent`, in spawn order.
+pub fn children(world: &World, parent: Entity) -> Vec<Entity> {
+    let mut children = Vec::new();
+    world.query::<&Parent>(|entity, of| {
+        if of.0 == parent {
+            children.push(entity);
+        }
+    });
+    children.sort();
+    children
+}
+
+/// Despawns `entity` and all of its descendants, returning how many
+/// entities were removed.
+pub fn despawn_recursive(world: &mut World, entity: Entity) -> usize {
+    let mut removed = 0;
+    let mut pending = vec![entity];
+    while let Some(entity) = pending.pop() {
+        if !world.is_alive(entity) {
+            continue;
+        }
+        pending.extend(children(world, entity));
+        world.despawn(entity);
+        removed += 1;
+    }
+    removed
+}
+
+struct Resolver<'a, F> {
+    nodes: &'a HashMap<Entity, (Transform2D, Option<Entity>)>,
+    root: &'a F,
+    resolved: HashMap<Entity, Transform2D>,
+    /// Entities being resolved, each the child of the one after it.
+    path: Vec<Entity>,
+}
+
+impl<F: Fn(Entity) -> Transform2D> Resolver<'_, F> {
+    fn resolve(&mut self, entity: Entity) -> Transform2D {
+        if let Some(&transform) = self.resolved.get(&entity) {
+            return transform;
+        }
+        let Some(&(local, parent)) = self.nodes.get(&entity) else {
+            return (self.root)(entity);
+        };
+
+        self.path.push(entity);
+        let cycle = parent.and_then(|parent| {
+            self.path
+                .iter()
+                .position(|&visiting| visiting == parent)
+        });
+        match (parent, cycle) {
+            (_, Some(start)) => {
+                for &member in &self.path[start..] {
+                    self.resolved
+                        .insert(member, self.nodes[&member].0);
+                }
+            }
+            (Some(parent), None) => {
+                let transform = self.resolve(parent).then(&local);
+                // Resolving the parent may have found this in a cycle.
+                self.resolved
+                    .entry(entity)
+                    .or_insert(transform);
+            }
+            (None, None) => {
+                self.resolved.insert(entity, local);
+            }
+        }
+        self.path.pop();
+        self.resolved[&entity]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(x: f64, y: f64) -> LocalTransform {
+        LocalTransform(Transform2D::from_translation(Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn children_follow_their_parents() {
+        let mut world = World::new();
+        let tank = world
+            .spawn()
+            .with(Position { x: 100.0, y: 50.0 })
+            .with(Rotation(std::f64::consts::FRAC_PI_2))
+            .id();
+        let turret = world
+            .spawn()
+            .with(local(10.0, 0.0))
+            .with(Parent(tank))
+            .id();
+        let barrel = world
+            .spawn()
+            .with(local(5.0, 0.0))
+            .with(Parent(turret))
+            .id();
+
+        let transforms =
+            world_transforms(&world, |entity| root_transform(&world, entity));
+
+        assert!(!transforms.contains_key(&tank));
+        let barrel = transforms[&barrel].translation;
+        assert!((barrel - Vec2::new(100.0, 65.0)).length() < 1e-9);
+        assert_eq!(children(&world, tank), [turret]);
+    }
+
+    #[test]
+    fn cycles_and_orphans_become_roots() {
+        let mut world = World::new();
+        let a = world.spawn().with(local(1.0, 0.0)).id();
+        let b = world
+            .spawn()
+            .with(local(0.0, 1.0))
+            .with(Parent(a))
+            .id();
+        world.insert(a, Parent(b));
+        let c = world
+            .spawn()
+            .with(local(0.0, 2.0))
+            .with(Parent(a))
+            .id();
+        let gone = world.spawn().id();
+        let orphan = world
+            .spawn()
+            .with(local(3.0, 3.0))
+            .with(Parent(gone))
+            .id();
+        world.despawn(gone);
+
+        let transforms = world_transforms(&world, |_| Transform2D::IDENTITY);
+
+        assert_eq!(transforms.len(), 4);
+        assert_eq!(transforms[&a].translation, Vec2::new(1.0, 0.0));
+        assert_eq!(transforms[&b].translation, Vec2::new(0.0, 1.0));
+        assert_eq!(transforms[&c].translation, Vec2::new(1.0, 2.0));
+        assert_eq!(transforms[&orphan].translation, Vec2::new(3.0, 3.0));
+    }
+
+    #[test]
+    fn despawns_descendants() {
+        let mut world = World::new();
+        let root = world.spawn().id();
+        let child = world.spawn().with(Parent(root)).id();
+        world.spawn().with(Parent(child));
+        let other = world.spawn().id();
+
+        assert_eq!(despawn_recursive(&mut world, root), 3);
+        assert_eq!(world.entities().collect::<Vec<_>>(), [other]);
+    }
+}
@@ -0,0 +1,238 @@
+//! CPU-side RGBA images and file decoding.
+//!
+//! Supported formats are detected from the file's magic bytes: PNG, BMP,
+//! binary PPM (`P6`) and QOI.
+
+mod bmp;
+mod qoi;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::color::Color;
+use crate::framebuffer::Framebuffer;
+use crate::ppm;
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    UnsupportedFormat,
+    Malformed { format: &'static str, message: String },
+}
+
+impl ImageError {
+    pub(crate) fn malformed(
+        format: &'static str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::Malformed { format, message: message.into() }
+    }
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read image: {err}"),
+            Self::UnsupportedFormat => write!(f, "unsupported image format"),
+            Self::Malformed { format, message } => {
+                write!(f, "malformed {format} image: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ImageError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Image {
+    /// Creates a fully transparent image.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::TRANSPARENT; width * height],
+        }
+    }
+
+    /// Wraps row-major pixels; `pixels.len()` must equal `width * height`.
+    pub fn from_pixels(
+        width: usize,
+        height: usize,
+        pixels: Vec<Color>,
+    ) -> Self {
+        assert_eq!(pixels.len(), width * height, "pixel count mismatch");
+        Self { width, height, pixels }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    /// Decodes an image, picking the format from its magic bytes.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ImageError> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            decode_png(bytes)
+        } else if bytes.starts_with(b"BM") {
+            bmp::decode(bytes)
+        } else if bytes.starts_with(b"qoif") {
+            qoi::decode(bytes)
+        } else if bytes.starts_with(b"P6") {
+            ppm::decode(bytes)
+                .map(|framebuffer| Self::from(&framebuffer))
+                .map_err(|err| ImageError::malformed("PPM", err.to_string()))
+        } else {
+            Err(ImageError::UnsupportedFormat)
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.pixels
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(self.pixels[y * self.width + x])
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        self.pixels[y * self.width + x] = color;
+    }
+}
+
+impl From<&Framebuffer> for Image {
+    fn from(framebuffer: &Framebuffer) -> Self {
+        let pixels = framebuffer
+            .pixels()
+            .iter()
+            .map(|&pixel| Color::from_0rgb(pixel))
+            .collect();
+        Self::from_pixels(framebuffer.width(), framebuffer.height(), pixels)
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Image, ImageError> {
+    // Reading from memory, so I/O errors here mean truncated data.
+    let malformed =
+        |err: png::DecodingError| ImageError::malformed("PNG", err.to_string());
+
+    let mut decoder = png::Decoder::new(io::Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(malformed)?;
+
+    let buffer_size = reader
+        .output_buffer_size()
+        .ok_or_else(|| ImageError::malformed("PNG", "image is too large"))?;
+    let mut buffer = vec![0; buffer_size];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(malformed)?;
+
+    let (width, height) = (info.width as usize, info.height as usize);
+    let channels = info.color_type.samples();
+    let pixels = buffer
+        .chunks(info.line_size)
+        .take(height)
+        .flat_map(|line| line[..width * channels].chunks_exact(channels))
+        .map(|px| match *px {
+            [l] => Color::rgb(l, l, l),
+            [l, a] => Color::rgba(l, l, l, a),
+            [r, g, b] => Color::rgb(r, g, b),
+            [r, g, b, a] => Color::rgba(r, g, b, a),
+            _ => unreachable!("PNG pixels have 1 to 4 samples"),
+        })
+        .collect();
+
+    Ok(Image::from_pixels(width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(rgba).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    #[test]
+    fn decodes_png() {
+        let bytes = encode_png(2, 1, &[255, 0, 0, 255, 0, 0, 255, 128]);
+
+        let image = Image::decode(&bytes).unwrap();
+
+        assert_eq!(
+            image.pixels(),
+            [Color::RED, Color::rgba(0, 0, 255, 128)].as_slice()
+        );
+    }
+
+    #[test]
+    fn decodes_ppm() {
+        let image = Image::decode(b"P6 1 1 255 \x10\x20\x30").unwrap();
+
+        assert_eq!(image.pixel(0, 0), Some(Color::rgb(0x10, 0x20, 0x30)));
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        assert!(matches!(
+            Image::decode(b"GIF89a"),
+            Err(ImageError::UnsupportedFormat)
+        ));
+    }
+
+    #[test]
+    fn reports_truncated_png() {
+        let bytes = encode_png(4, 4, &[0; 64]);
+
+        let err = Image::decode(&bytes[..bytes.len() / 2]).unwrap_err();
+
+        assert!(err.to_string().contains("PNG"), "{err}");
+    }
+}
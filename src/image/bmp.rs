@@ -0,0 +1,225 @@
+use crate::color::Color;
+use crate::image::{Image, ImageError};
+
+const FILE_HEADER_LEN: usize = 14;
+const INFO_HEADER_LEN: usize = 40;
+
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+/// Decodes uncompressed 1/4/8-bit palettized and 16/24/32-bit BMPs,
+/// including `BI_BITFIELDS` channel masks.
+pub(super) fn decode(bytes: &[u8]) -> Result<Image, ImageError> {
+    let pixel_offset = read_u32(bytes, 10)? as usize;
+    let header_len = read_u32(bytes, 14)? as usize;
+    if header_len < INFO_HEADER_LEN {
+        return Err(malformed("OS/2 core headers are not supported"));
+    }
+
+    let width = read_u32(bytes, 18)? as i32;
+    let raw_height = read_u32(bytes, 22)? as i32;
+    let bits_per_pixel = read_u16(bytes, 28)?;
+    let compression = read_u32(bytes, 30)?;
+    let colors_used = read_u32(bytes, 46)? as usize;
+
+    if width <= 0 || raw_height == 0 || raw_height == i32::MIN {
+        return Err(malformed("invalid dimensions"));
+    }
+    let (width, height) = (width as usize, raw_height.unsigned_abs() as usize);
+    let top_down = raw_height < 0;
+
+    let masks = match compression {
+        BI_RGB => default_masks(bits_per_pixel),
+        BI_BITFIELDS | BI_ALPHABITFIELDS => {
+            // Masks follow a 40-byte header, or live inside V4/V5 headers.
+            let at = FILE_HEADER_LEN + INFO_HEADER_LEN;
+            let alpha = if compression == BI_ALPHABITFIELDS
+                || header_len > INFO_HEADER_LEN
+            {
+                read_u32(bytes, at + 12)?
+            } else {
+                0
+            };
+            [
+                read_u32(bytes, at)?,
+                read_u32(bytes, at + 4)?,
+                read_u32(bytes, at + 8)?,
+                alpha,
+            ]
+        }
+        other => {
+            return Err(malformed(format!(
+                "compression {other} is not supported"
+            )));
+        }
+    };
+
+    let palette = if bits_per_pixel <= 8 {
+        let count =
+            if colors_used == 0 { 1 << bits_per_pixel } else { colors_used };
+        let start = FILE_HEADER_LEN + header_len;
+        let entries = bytes
+            .get(start..start + count * 4)
+            .ok_or_else(|| malformed("palette is truncated"))?;
+        entries
+            .chunks_exact(4)
+            .map(|bgrx| Color::rgb(bgrx[2], bgrx[1], bgrx[0]))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let stride = (width * bits_per_pixel as usize).div_ceil(32) * 4;
+    let data = bytes
+        .get(pixel_offset..pixel_offset + stride * height)
+        .ok_or_else(|| malformed("pixel data is truncated"))?;
+
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let row_index = if top_down { y } else { height - 1 - y };
+        let row = &data[row_index * stride..(row_index + 1) * stride];
+
+        for x in 0..width {
+            let color = match bits_per_pixel {
+                1 | 4 | 8 => {
+                    let bit = x * bits_per_pixel as usize;
+                    let byte = row[bit / 8];
+                    let shift = 8 - bits_per_pixel as usize - bit % 8;
+                    let index =
+                        (byte >> shift) & ((1u16 << bits_per_pixel) - 1) as u8;
+                    *palette
+                        .get(index as usize)
+                        .ok_or_else(|| {
+                            malformed("palette index out of range")
+                        })?
+                }
+                16 => {
+                    let value =
+                        u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]);
+                    from_masks(value as u32, masks)
+                }
+                24 => Color::rgb(row[x * 3 + 2], row[x * 3 + 1], row[x * 3]),
+                32 => {
+                    let value = u32::from_le_bytes(
+                        row[x * 4..x * 4 + 4]
+                            .try_into()
+                            .unwrap(),
+                    );
+                    from_masks(value, masks)
+                }
+                other => {
+                    return Err(malformed(format!(
+                        "{other} bits per pixel is not supported"
+                    )));
+                }
+            };
+            pixels.push(color);
+        }
+    }
+
+    Ok(Image::from_pixels(width, height, pixels))
+}
+
+fn default_masks(bits_per_pixel: u16) -> [u32; 4] {
+    match bits_per_pixel {
+        16 => [0x7C00, 0x03E0, 0x001F, 0],
+        _ => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0],
+    }
+}
+
+/// Extracts channels with the given RGBA masks; a zero alpha mask means
+/// the image is opaque.
+fn from_masks(value: u32, [r, g, b, a]: [u32; 4]) -> Color {
+    let channel = |mask: u32| -> u8 {
+        if mask == 0 {
+            return 0;
+        }
+        let bits = mask.count_ones();
+        let raw = (value & mask) >> mask.trailing_zeros();
+        let max = (1u64 << bits) - 1;
+        ((raw as u64 * 255 + max / 2) / max) as u8
+    };
+
+    let alpha = if a == 0 { 255 } else { channel(a) };
+    Color::rgba(channel(r), channel(g), channel(b), alpha)
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Result<u16, ImageError> {
+    bytes
+        .get(at..at + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| malformed("header is truncated"))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Result<u32, ImageError> {
+    bytes
+        .get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| malformed("header is truncated"))
+}
+
+fn malformed(message: impl Into<String>) -> ImageError {
+    ImageError::malformed("BMP", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bmp(
+        width: i32,
+        height: i32,
+        bpp: u16,
+        extra: &[u8],
+        data: &[u8],
+    ) -> Vec<u8> {
+        let offset = (FILE_HEADER_LEN + INFO_HEADER_LEN + extra.len()) as u32;
+        let mut bytes = b"BM".to_vec();
+        bytes.extend((offset + data.len() as u32).to_le_bytes());
+        bytes.extend([0; 4]);
+        bytes.extend(offset.to_le_bytes());
+        bytes.extend((INFO_HEADER_LEN as u32).to_le_bytes());
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(bpp.to_le_bytes());
+        bytes.extend([0; 24]);
+        bytes.extend(extra);
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn decodes_bottom_up_24_bit_with_padding() {
+        // Rows are 6 bytes of BGR padded to 8, stored bottom row first.
+        let data = [
+            0, 0, 255, 0, 255, 0, 0, 0, //
+            255, 0, 0, 255, 255, 255, 0, 0,
+        ];
+        let image = decode(&bmp(2, 2, 24, &[], &data)).unwrap();
+
+        assert_eq!(image.pixel(0, 0), Some(Color::BLUE));
+        assert_eq!(image.pixel(1, 0), Some(Color::WHITE));
+        assert_eq!(image.pixel(0, 1), Some(Color::RED));
+        assert_eq!(image.pixel(1, 1), Some(Color::GREEN));
+    }
+
+    #[test]
+    fn decodes_top_down_palettized() {
+        let palette = [0, 0, 0, 0, 255, 255, 255, 0];
+        let data = [0b1000_0000, 0, 0, 0, 0b0100_0000, 0, 0, 0];
+        let image = decode(&bmp(2, -2, 1, &palette, &data)).unwrap();
+
+        assert_eq!(image.pixel(0, 0), Some(Color::WHITE));
+        assert_eq!(image.pixel(1, 0), Some(Color::BLACK));
+        assert_eq!(image.pixel(1, 1), Some(Color::WHITE));
+    }
+
+    #[test]
+    fn rejects_truncated_pixels() {
+        let bytes = bmp(4, 4, 24, &[], &[0; 8]);
+
+        assert!(decode(&bytes).is_err());
+    }
+}
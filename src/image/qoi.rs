@@ -0,0 +1,149 @@
+use crate::color::Color;
+use crate::image::{Image, ImageError};
+
+const HEADER_LEN: usize = 14;
+
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const OP_RUN: u8 = 0xC0;
+const OP_MASK: u8 = 0xC0;
+
+/// Decodes a "Quite OK Image" per the QOI 1.0 specification.
+pub(super) fn decode(bytes: &[u8]) -> Result<Image, ImageError> {
+    let header = bytes
+        .get(..HEADER_LEN)
+        .ok_or_else(|| malformed("header is truncated"))?;
+    let width = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+    let height = u32::from_be_bytes(header[8..12].try_into().unwrap()) as usize;
+    if !matches!(header[12], 3 | 4) {
+        return Err(malformed("channel count must be 3 or 4"));
+    }
+
+    let pixel_count = width
+        .checked_mul(height)
+        .ok_or_else(|| malformed("image is too large"))?;
+    let mut pixels = Vec::with_capacity(pixel_count.min(bytes.len() * 62));
+
+    let mut index = [Color::TRANSPARENT; 64];
+    let mut px = Color::BLACK;
+    let mut cursor = HEADER_LEN;
+    let mut next = || -> Result<u8, ImageError> {
+        let byte = *bytes
+            .get(cursor)
+            .ok_or_else(|| malformed("pixel data is truncated"))?;
+        cursor += 1;
+        Ok(byte)
+    };
+
+    while pixels.len() < pixel_count {
+        let op = next()?;
+        let mut run = 1;
+
+        match op {
+            OP_RGB => {
+                px = Color { r: next()?, g: next()?, b: next()?, ..px };
+            }
+            OP_RGBA => {
+                px = Color::rgba(next()?, next()?, next()?, next()?);
+            }
+            _ => match op & OP_MASK {
+                OP_INDEX => px = index[op as usize],
+                OP_DIFF => {
+                    let delta =
+                        |shift: u8| ((op >> shift) & 0x03).wrapping_sub(2);
+                    px.r = px.r.wrapping_add(delta(4));
+                    px.g = px.g.wrapping_add(delta(2));
+                    px.b = px.b.wrapping_add(delta(0));
+                }
+                OP_LUMA => {
+                    let dg = (op & 0x3F).wrapping_sub(32);
+                    let byte = next()?;
+                    let dr = (byte >> 4)
+                        .wrapping_sub(8)
+                        .wrapping_add(dg);
+                    let db = (byte & 0x0F)
+                        .wrapping_sub(8)
+                        .wrapping_add(dg);
+                    px.r = px.r.wrapping_add(dr);
+                    px.g = px.g.wrapping_add(dg);
+                    px.b = px.b.wrapping_add(db);
+                }
+                OP_RUN => run = (op & 0x3F) as usize + 1,
+                _ => unreachable!("two-bit op tags are exhaustive"),
+            },
+        }
+
+        index[hash(px)] = px;
+        let run = run.min(pixel_count - pixels.len());
+        pixels.extend(std::iter::repeat_n(px, run));
+    }
+
+    Ok(Image::from_pixels(width, height, pixels))
+}
+
+fn hash(color: Color) -> usize {
+    (color.r as usize * 3
+        + color.g as usize * 5
+        + color.b as usize * 7
+        + color.a as usize * 11)
+        % 64
+}
+
+fn malformed(message: &str) -> ImageError {
+    ImageError::malformed("QOI", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qoi(width: u32, height: u32, ops: &[u8]) -> Vec<u8> {
+        let mut bytes = b"qoif".to_vec();
+        bytes.extend(width.to_be_bytes());
+        bytes.extend(height.to_be_bytes());
+        bytes.extend([4, 0]);
+        bytes.extend(ops);
+        bytes.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+        bytes
+    }
+
+    #[test]
+    fn decodes_every_op() {
+        let red = Color::rgb(200, 10, 10);
+        #[rustfmt::skip]
+        let ops = [
+            OP_RGB, 200, 10, 10,            // red
+            OP_RUN | 1,                     // red x2
+            OP_DIFF | 0b11_10_01,           // r+1, g+0, b-1
+            OP_LUMA | 34, 0x97,             // dg=+2, dr=+3, db=+1
+            OP_RGBA, 0, 0, 255, 128,
+            OP_INDEX | hash(red) as u8,
+        ];
+
+        let image = decode(&qoi(7, 1, &ops)).unwrap();
+
+        assert_eq!(
+            image.pixels(),
+            [
+                red,
+                red,
+                red,
+                Color::rgb(201, 10, 9),
+                Color::rgb(204, 12, 10),
+                Color::rgba(0, 0, 255, 128),
+                red,
+            ]
+            .as_slice()
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_stream() {
+        let bytes = qoi(4, 4, &[OP_RUN | 3]);
+
+        assert!(decode(&bytes[..bytes.len() - 8]).is_err());
+    }
+}
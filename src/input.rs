@@ -0,0 +1,73 @@
+//! Player input: bindings from physical keys and mouse buttons to named
+//! actions and axes, per-tick button state, and recording for replay.
+
+mod map;
+mod record;
+mod state;
+
+use std::fmt;
+use std::io;
+
+pub use map::{AxisBinding, Binding, Button, InputMap, Modifiers};
+pub use record::{InputEvent, InputRecorder, InputRecording, RecordedEvent};
+pub use state::InputState;
+
+/// Action names used by the built-in bindings.
+pub mod actions {
+    pub const TELEPORT: &str = "teleport";
+    pub const TOGGLE_DEBUG: &str = "toggle_debug";
+    pub const TOGGLE_INSPECTOR: &str = "toggle_inspector";
+
+    pub const MOVE_X: &str = "move_x";
+    pub const MOVE_Y: &str = "move_y";
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Io(io::Error),
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Serialize(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to access bindings: {err}"),
+            Self::Parse { line, column, message } => {
+                write!(f, "bindings {line}:{column}: {message}")
+            }
+            Self::Serialize(message) => {
+                write!(f, "failed to serialize bindings: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse { .. } | Self::Serialize(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for InputError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for InputError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Parse {
+            line: err.span.start.line,
+            column: err.span.start.col,
+            message: err.code.to_string(),
+        }
+    }
+}
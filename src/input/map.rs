@@ -0,0 +1,352 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use winit::event::MouseButton;
+use winit::keyboard::{KeyCode, ModifiersState};
+
+use crate::input::InputError;
+use crate::input::actions::{
+    MOVE_X, MOVE_Y, TELEPORT, TOGGLE_DEBUG, TOGGLE_INSPECTOR,
+};
+
+/// A physical key or mouse button. Keys are identified by position, so
+/// `KeyW` is the key labelled Z on AZERTY layouts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Button {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl From<KeyCode> for Button {
+    fn from(code: KeyCode) -> Self {
+        Self::Key(code)
+    }
+}
+
+impl From<MouseButton> for Button {
+    fn from(button: MouseButton) -> Self {
+        Self::Mouse(button)
+    }
+}
+
+/// Modifier keys held alongside a [`Button`].
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// The Windows, Command or Super key.
+    pub logo: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self {
+        shift: false,
+        ctrl: false,
+        alt: false,
+        logo: false,
+    };
+
+    fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+impl From<ModifiersState> for Modifiers {
+    fn from(state: ModifiersState) -> Self {
+        Self {
+            shift: state.shift_key(),
+            ctrl: state.control_key(),
+            alt: state.alt_key(),
+            logo: state.super_key(),
+        }
+    }
+}
+
+/// A button that triggers an action when pressed with exactly these
+/// modifiers held.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Binding {
+    pub button: Button,
+    #[serde(default, skip_serializing_if = "Modifiers::is_none")]
+    pub modifiers: Modifiers,
+}
+
+impl Binding {
+    pub fn new(button: impl Into<Button>) -> Self {
+        Self {
+            button: button.into(),
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn matches(&self, button: Button, modifiers: Modifiers) -> bool {
+        self.button == button && self.modifiers == modifiers
+    }
+}
+
+/// Pair of buttons driving an axis towards -1 and +1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AxisBinding {
+    pub negative: Button,
+    pub positive: Button,
+}
+
+impl AxisBinding {
+    pub fn new(
+        negative: impl Into<Button>,
+        positive: impl Into<Button>,
+    ) -> Self {
+        Self {
+            negative: negative.into(),
+            positive: positive.into(),
+        }
+    }
+}
+
+/// Named actions and axes with their bindings.
+///
+/// Serialized as RON, e.g.
+///
+/// ```ron
+/// (
+///     actions: {
+///         "teleport": [(button: Mouse(Left))],
+///         "save": [(button: Key(KeyS), modifiers: (ctrl: true))],
+///     },
+///     axes: {
+///         "move_x": [(negative: Key(KeyA), positive: Key(KeyD))],
+///     },
+/// )
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputMap {
+    #[serde(default)]
+    actions: BTreeMap<String, Vec<Binding>>,
+    #[serde(default)]
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+}
+
+impl InputMap {
+    /// An empty map with no bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The bindings used when no config file is available: WASD or the
+    /// arrow keys to move, left click to teleport, F3 for the overlay
+    /// and F2 for the inspector window.
+    pub fn builtin() -> Self {
+        let mut map = Self::new();
+        map.bind(TELEPORT, Binding::new(MouseButton::Left));
+        map.bind(TOGGLE_DEBUG, Binding::new(KeyCode::F3));
+        map.bind(TOGGLE_INSPECTOR, Binding::new(KeyCode::F2));
+
+        map.bind_axis(MOVE_X, AxisBinding::new(KeyCode::KeyA, KeyCode::KeyD));
+        map.bind_axis(
+            MOVE_X,
+            AxisBinding::new(KeyCode::ArrowLeft, KeyCode::ArrowRight),
+        );
+        map.bind_axis(MOVE_Y, AxisBinding::new(KeyCode::KeyW, KeyCode::KeyS));
+        map.bind_axis(
+            MOVE_Y,
+            AxisBinding::new(KeyCode::ArrowUp, KeyCode::ArrowDown),
+        );
+        map
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_ron(source: &str) -> Result<Self, InputError> {
+        Ok(ron::from_str(source)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputError> {
+        fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+
+    pub fn to_ron(&self) -> Result<String, InputError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| InputError::Serialize(err.to_string()))
+    }
+
+    /// Adds a binding for `action`, keeping any existing ones.
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        let bindings = self
+            .actions
+            .entry(action.to_owned())
+            .or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes one binding from `action`. Returns whether it was bound.
+    pub fn unbind(&mut self, action: &str, binding: &Binding) -> bool {
+        let Some(bindings) = self.actions.get_mut(action) else {
+            return false;
+        };
+        let before = bindings.len();
+        bindings.retain(|b| b != binding);
+        before != bindings.len()
+    }
+
+    /// Replaces every binding of `action` with `binding`.
+    pub fn rebind(&mut self, action: &str, binding: Binding) {
+        self.actions
+            .insert(action.to_owned(), vec![binding]);
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions
+            .get(action)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        let bindings = self
+            .axes
+            .entry(axis.to_owned())
+            .or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Replaces every binding of `axis` with `binding`.
+    pub fn rebind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        self.axes
+            .insert(axis.to_owned(), vec![binding]);
+    }
+
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes
+            .get(axis)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Actions triggered by pressing `button` with `modifiers` held.
+    pub fn actions_for(
+        &self,
+        button: Button,
+        modifiers: Modifiers,
+    ) -> impl Iterator<Item = &str> + '_ {
+        self.actions
+            .iter()
+            .filter(move |(_, bindings)| {
+                bindings
+                    .iter()
+                    .any(|b| b.matches(button, modifiers))
+            })
+            .map(|(action, _)| action.as_str())
+    }
+
+    /// Value of `axis` in -1..=1, given which buttons are held down.
+    pub fn axis(&self, axis: &str, is_down: impl Fn(Button) -> bool) -> f32 {
+        let held = |button| if is_down(button) { 1.0 } else { 0.0 };
+        let value: f32 = self
+            .axis_bindings(axis)
+            .iter()
+            .map(|b| held(b.positive) - held(b.negative))
+            .sum();
+        value.clamp(-1.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_require_exact_modifiers() {
+        let mut map = InputMap::builtin();
+        let ctrl = Modifiers { ctrl: true, ..Modifiers::NONE };
+        map.bind("save", Binding::new(KeyCode::KeyS).with_modifiers(ctrl));
+        map.bind("crouch", Binding::new(KeyCode::KeyS));
+
+        let plain: Vec<_> = map
+            .actions_for(KeyCode::KeyS.into(), Modifiers::NONE)
+            .collect();
+        let with_ctrl: Vec<_> = map
+            .actions_for(KeyCode::KeyS.into(), ctrl)
+            .collect();
+
+        assert_eq!(plain, ["crouch"]);
+        assert_eq!(with_ctrl, ["save"]);
+    }
+
+    #[test]
+    fn rebinding_replaces_previous_bindings() {
+        let mut map = InputMap::builtin();
+        map.rebind(TELEPORT, Binding::new(MouseButton::Right));
+
+        assert_eq!(
+            map.actions_for(MouseButton::Left.into(), Modifiers::NONE)
+                .count(),
+            0
+        );
+        assert_eq!(map.bindings(TELEPORT), [Binding::new(MouseButton::Right)]);
+    }
+
+    #[test]
+    fn axes_combine_and_clamp() {
+        let map = InputMap::builtin();
+        let held = |keys: &'static [KeyCode]| {
+            move |button| {
+                keys.iter()
+                    .any(|&k| Button::Key(k) == button)
+            }
+        };
+
+        assert_eq!(map.axis(MOVE_X, held(&[KeyCode::KeyD])), 1.0);
+        assert_eq!(
+            map.axis(MOVE_X, held(&[KeyCode::KeyA, KeyCode::KeyD])),
+            0.0
+        );
+        assert_eq!(
+            map.axis(MOVE_Y, held(&[KeyCode::KeyW, KeyCode::ArrowUp])),
+            -1.0
+        );
+    }
+
+    #[test]
+    fn round_trips_through_ron() {
+        let map = InputMap::builtin();
+
+        let parsed = InputMap::from_ron(&map.to_ron().unwrap()).unwrap();
+
+        assert_eq!(parsed, map);
+    }
+
+    #[test]
+    fn parses_modifiers_and_reports_errors_with_position() {
+        let source = r#"(
+            actions: {
+                "save": [(button: Key(KeyS), modifiers: (ctrl: true))],
+            },
+        )"#;
+        let map = InputMap::from_ron(source).unwrap();
+        assert!(map.bindings("save")[0].modifiers.ctrl);
+
+        let err =
+            InputMap::from_ron("(\n  actions: { \"a\": [Key(Nope)] },\n)")
+                .unwrap_err();
+        assert!(
+            matches!(err, InputError::Parse { line: 2, .. }),
+            "unexpected error: {err}"
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use winit::event::WindowEvent;
+use winit::keyboard::PhysicalKey;
+
+use crate::canvas::VirtualCanvas;
+use crate::graphics::GraphicsState;
+use crate::input::{Button, InputError, InputMap, Modifiers};
+
+/// Window input that can affect the simulation.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Press(Button),
+    Release(Button),
+    Modifiers(Modifiers),
+    CursorMoved { x: f64, y: f64 },
+    Resized { width: u32, height: u32 },
+    ScaleFactorChanged { scale_factor: f64 },
+    FocusLost,
+}
+
+impl InputEvent {
+    /// The input carried by a window event, if any.
+    pub fn from_window_event(event: &WindowEvent) -> Option<Self> {
+        match event {
+            WindowEvent::KeyboardInput { event, .. } => {
+                let PhysicalKey::Code(code) = event.physical_key else {
+                    return None;
+                };
+                Some(if event.state.is_pressed() {
+                    Self::Press(Button::Key(code))
+                } else {
+                    Self::Release(Button::Key(code))
+                })
+            }
+            WindowEvent::MouseInput { button, state, .. } => {
+                Some(if state.is_pressed() {
+                    Self::Press(Button::Mouse(*button))
+                } else {
+                    Self::Release(Button::Mouse(*button))
+                })
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                Some(Self::Modifiers(modifiers.state().into()))
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                Some(Self::CursorMoved { x: position.x, y: position.y })
+            }
+            WindowEvent::Resized(size) => {
+                Some(Self::Resized { width: size.width, height: size.height })
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                Some(Self::ScaleFactorChanged { scale_factor: *scale_factor })
+            }
+            WindowEvent::Focused(false) => Some(Self::FocusLost),
+            _ => None,
+        }
+    }
+}
+
+/// An input event and the simulation tick it arrived before.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub tick: u64,
+    pub event: InputEvent,
+}
+
+/// Everything needed to rerun a session tick for tick.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub tick_rate: f64,
+    /// Window size in physical pixels when recording started.
+    pub width: u32,
+    pub height: u32,
+    #[serde(default = "default_scale_factor")]
+    pub scale_factor: f64,
+    /// Logical resolution, if the session rendered at a fixed one.
+    #[serde(default)]
+    pub canvas: Option<VirtualCanvas>,
+    /// Bindings in effect, so edits to the config can't change a replay.
+    pub bindings: InputMap,
+    /// Number of ticks the session ran.
+    pub ticks: u64,
+    pub events: Vec<RecordedEvent>,
+}
+
+impl InputRecording {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        Ok(ron::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputError> {
+        let source = ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default().compact_arrays(true),
+        )
+        .map_err(|err| InputError::Serialize(err.to_string()))?;
+        fs::write(path, source)?;
+        Ok(())
+    }
+
+    /// A fresh state at the recorded size with the recorded bindings.
+    pub fn initial_state(&self) -> GraphicsState {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut state = match self.canvas {
+            Some(canvas) => {
+                let mut state =
+                    GraphicsState::with_canvas(canvas, width, height);
+                state.set_scale_factor(self.scale_factor);
+                state
+            }
+            None => GraphicsState::for_window(width, height, self.scale_factor),
+        };
+        state.set_input_map(self.bindings.clone());
+        state
+    }
+
+    /// Feeds the recorded events into `state` and runs every tick,
+    /// leaving it as the recorded session ended.
+    pub fn replay(&self, state: &mut GraphicsState) {
+        let dt = 1.0 / self.tick_rate;
+        let mut events = self.events.iter().peekable();
+
+        for tick in 0..=self.ticks {
+            while let Some(recorded) =
+                events.next_if(|recorded| recorded.tick <= tick)
+            {
+                state.apply_input(&recorded.event);
+            }
+            if tick < self.ticks {
+                state.update(dt);
+            }
+        }
+    }
+}
+
+fn default_scale_factor() -> f64 {
+    1.0
+}
+
+/// Collects input events against the tick they will be consumed by.
+#[derive(Clone, Debug)]
+pub struct InputRecorder {
+    recording: InputRecording,
+}
+
+impl InputRecorder {
+    pub fn new(
+        tick_rate: f64,
+        width: u32,
+        height: u32,
+        bindings: InputMap,
+    ) -> Self {
+        Self {
+            recording: InputRecording {
+                tick_rate,
+                width,
+                height,
+                scale_factor: 1.0,
+                canvas: None,
+                bindings,
+                ticks: 0,
+                events: Vec::new(),
+            },
+        }
+    }
+
+    pub fn with_scale_factor(mut self, scale_factor: f64) -> Self {
+        self.recording.scale_factor = scale_factor;
+        self
+    }
+
+    pub fn with_canvas(mut self, canvas: Option<VirtualCanvas>) -> Self {
+        self.recording.canvas = canvas;
+        self
+    }
+
+    pub fn record(&mut self, event: InputEvent) {
+        let tick = self.recording.ticks;
+        self.recording
+            .events
+            .push(RecordedEvent { tick, event });
+    }
+
+    /// Call after each simulation tick.
+    pub fn end_tick(&mut self) {
+        self.recording.ticks += 1;
+    }
+
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    pub fn finish(self) -> InputRecording {
+        self.recording
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+    use winit::keyboard::KeyCode;
+
+    use super::*;
+
+    const DT: f64 = 1.0 / 60.0;
+
+    /// Events fed to a live state, keyed by the tick they arrive before.
+    fn script(tick: u64) -> Vec<InputEvent> {
+        let d = Button::Key(KeyCode::KeyD);
+        match tick {
+            5 => vec![InputEvent::Press(d)],
+            20 => vec![InputEvent::Release(d)],
+            30 => vec![
+                InputEvent::CursorMoved { x: 20.0, y: 90.0 },
+                InputEvent::Press(Button::Mouse(MouseButton::Left)),
+            ],
+            40 => vec![InputEvent::Resized { width: 120, height: 100 }],
+            _ => Vec::new(),
+        }
+    }
+
+    fn record_live_session() -> (GraphicsState, InputRecording) {
+        let mut live = GraphicsState::new(160, 120);
+        let mut recorder =
+            InputRecorder::new(60.0, 160, 120, InputMap::builtin());
+        for tick in 0..90 {
+            for event in script(tick) {
+                recorder.record(event);
+                live.apply_input(&event);
+            }
+            live.update(DT);
+            recorder.end_tick();
+        }
+        (live, recorder.finish())
+    }
+
+    #[test]
+    fn replay_matches_the_live_session() {
+        let (mut live, recording) = record_live_session();
+
+        let mut replayed = recording.initial_state();
+        recording.replay(&mut replayed);
+        live.render(1.0);
+        replayed.render(1.0);
+
+        assert_eq!(recording.ticks, 90);
+        assert_eq!(replayed.square_position(), live.square_position());
+        assert_eq!(replayed.framebuffer, live.framebuffer);
+    }
+
+    #[test]
+    fn round_trips_through_ron() {
+        let (_, recording) = record_live_session();
+        let path = std::env::temp_dir()
+            .join(format!("window_app_recording_{}.ron", std::process::id()));
+
+        recording.save(&path).unwrap();
+        let loaded = InputRecording::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, recording);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use crate::input::{Button, InputEvent, InputMap, Modifiers};
+
+/// Buttons held down, plus edges since the last simulation tick.
+///
+/// Feed it window events with [`press`] and [`release`]; the update step
+/// reads it and then calls [`end_tick`] so a press is seen exactly once
+/// even when a frame runs several ticks or none at all.
+///
+/// [`press`]: InputState::press
+/// [`release`]: InputState::release
+/// [`end_tick`]: InputState::end_tick
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    down: HashSet<Button>,
+    just_pressed: HashSet<Button>,
+    just_released: HashSet<Button>,
+    modifiers: Modifiers,
+    cursor: (f64, f64),
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the state from one event. Resizes and scale factor changes
+    /// are left to the caller.
+    pub fn apply(&mut self, event: &InputEvent) {
+        match *event {
+            InputEvent::Press(button) => self.press(button),
+            InputEvent::Release(button) => self.release(button),
+            InputEvent::Modifiers(modifiers) => self.set_modifiers(modifiers),
+            InputEvent::CursorMoved { x, y } => self.set_cursor(x, y),
+            InputEvent::FocusLost => self.release_all(),
+            InputEvent::Resized { .. }
+            | InputEvent::ScaleFactorChanged { .. } => {}
+        }
+    }
+
+    /// Marks `button` as held. OS key repeats of a held button are ignored.
+    pub fn press(&mut self, button: Button) {
+        if self.down.insert(button) {
+            self.just_pressed.insert(button);
+        }
+    }
+
+    pub fn release(&mut self, button: Button) {
+        if self.down.remove(&button) {
+            self.just_released.insert(button);
+        }
+    }
+
+    /// Releases everything, e.g. when the window loses focus and would
+    /// otherwise miss the key-up events.
+    pub fn release_all(&mut self) {
+        self.just_released
+            .extend(self.down.drain());
+        self.modifiers = Modifiers::NONE;
+    }
+
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    pub fn set_cursor(&mut self, x: f64, y: f64) {
+        self.cursor = (x, y);
+    }
+
+    /// Last known cursor position in window pixels.
+    pub fn cursor(&self) -> (f64, f64) {
+        self.cursor
+    }
+
+    pub fn is_down(&self, button: Button) -> bool {
+        self.down.contains(&button)
+    }
+
+    /// Pressed since the last tick.
+    pub fn just_pressed(&self, button: Button) -> bool {
+        self.just_pressed.contains(&button)
+    }
+
+    /// Released since the last tick.
+    pub fn just_released(&self, button: Button) -> bool {
+        self.just_released.contains(&button)
+    }
+
+    /// Whether any binding of `action` is held with its modifiers.
+    pub fn action_down(&self, map: &InputMap, action: &str) -> bool {
+        map.bindings(action)
+            .iter()
+            .any(|b| self.is_down(b.button) && b.modifiers == self.modifiers)
+    }
+
+    /// Whether a binding of `action` was pressed since the last tick.
+    pub fn action_just_pressed(&self, map: &InputMap, action: &str) -> bool {
+        map.bindings(action).iter().any(|b| {
+            self.just_pressed(b.button) && b.modifiers == self.modifiers
+        })
+    }
+
+    /// Value of `axis` in -1..=1 from the buttons held right now.
+    pub fn axis(&self, map: &InputMap, axis: &str) -> f32 {
+        map.axis(axis, |button| self.is_down(button))
+    }
+
+    /// Forgets the presses and releases seen by the tick that just ran.
+    pub fn end_tick(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::KeyCode;
+
+    use super::*;
+    use crate::input::actions::{MOVE_X, MOVE_Y};
+
+    const W: Button = Button::Key(KeyCode::KeyW);
+
+    #[test]
+    fn tracks_edges_until_the_tick_ends() {
+        let mut input = InputState::new();
+        input.press(W);
+        input.press(W);
+
+        assert!(input.is_down(W) && input.just_pressed(W));
+        input.end_tick();
+        assert!(input.is_down(W) && !input.just_pressed(W));
+
+        input.release(W);
+        assert!(!input.is_down(W) && input.just_released(W));
+        input.end_tick();
+        assert!(!input.just_released(W));
+    }
+
+    #[test]
+    fn tap_within_one_frame_is_still_seen() {
+        let mut input = InputState::new();
+        input.press(W);
+        input.release(W);
+
+        assert!(input.just_pressed(W) && input.just_released(W));
+        assert!(!input.is_down(W));
+    }
+
+    #[test]
+    fn axes_follow_held_keys() {
+        let map = InputMap::builtin();
+        let mut input = InputState::new();
+        input.press(Button::Key(KeyCode::KeyD));
+        input.press(W);
+
+        assert_eq!(input.axis(&map, MOVE_X), 1.0);
+        assert_eq!(input.axis(&map, MOVE_Y), -1.0);
+
+        input.release_all();
+        assert_eq!(input.axis(&map, MOVE_X), 0.0);
+    }
+}
@@ -0,0 +1,109 @@
+//! Read-only listing of a world's entities, for an editor window beside
+//! the game view.
+
+use std::fmt::Write;
+
+use crate::color::Color;
+use crate::components::{
+    AngularVelocity, Collider, Parent, PlayerControlled, Position, RigidBody,
+    Rotation, Velocity,
+};
+use crate::dpi::ui_scale;
+use crate::ecs::World;
+use crate::font::BitmapFont;
+use crate::framebuffer::Framebuffer;
+use crate::text::TextStyle;
+
+const BACKGROUND_COLOR: Color = Color::rgb(0x18, 0x18, 0x28);
+const TEXT_COLOR: Color = Color::rgb(0xE0, 0xE0, 0xE0);
+const MARGIN: i32 = 6;
+
+/// One line per entity with its transform, motion and body state.
+pub fn describe(world: &World) -> String {
+    let mut text = format!("{} entities\n", world.len());
+    for entity in world.entities() {
+        let _ = write!(text, "#{:<3}", entity.index());
+        if let Some(parent) = world.get::<Parent>(entity) {
+            let _ = write!(text, " child of #{}", parent.0.index());
+        }
+        if let Some(pos) = world.get::<Position>(entity) {
+            let _ = write!(text, " pos {:.1}, {:.1}", pos.x, pos.y);
+        }
+        if let Some(rotation) = world.get::<Rotation>(entity) {
+            let _ = write!(text, " rot {:.2}", rotation.0);
+        }
+        if let Some(vel) = world.get::<Velocity>(entity) {
+            let _ = write!(text, " vel {:.1}, {:.1}", vel.x, vel.y);
+        }
+        if let Some(spin) = world.get::<AngularVelocity>(entity) {
+            let _ = write!(text, " spin {:.2}", spin.0);
+        }
+        if world
+            .get::<PlayerControlled>(entity)
+            .is_some()
+        {
+            text.push_str(" player");
+        }
+        if world.get::<Collider>(entity).is_some() {
+            text.push_str(" collider");
+        }
+        if let Some(rigid) = world.get::<RigidBody>(entity) {
+            text.push_str(match (rigid.is_dynamic(), rigid.is_asleep()) {
+                (false, _) => " fixed",
+                (true, false) => " awake",
+                (true, true) => " asleep",
+            });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Draws [`describe`] as text filling a frame.
+#[derive(Clone, Debug)]
+pub struct Inspector {
+    font: BitmapFont,
+}
+
+impl Default for Inspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self { font: BitmapFont::builtin_8x16() }
+    }
+
+    /// Clears `framebuffer` and lists `world`'s entities, magnified by
+    /// the whole part of `scale_factor`.
+    pub fn draw(
+        &self,
+        world: &World,
+        framebuffer: &mut Framebuffer,
+        scale_factor: f64,
+    ) {
+        let scale = ui_scale(scale_factor);
+        let margin = MARGIN * scale as i32;
+        let text_w = framebuffer.width() as i32 - margin * 2;
+
+        framebuffer.fill(BACKGROUND_COLOR);
+        if text_w <= 0 {
+            return;
+        }
+        let style = TextStyle {
+            color: TEXT_COLOR,
+            scale,
+            max_width: Some(text_w),
+            ..TextStyle::default()
+        };
+        framebuffer.draw_text(
+            &self.font,
+            &describe(world),
+            margin,
+            margin,
+            &style,
+        );
+    }
+}
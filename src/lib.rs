@@ -0,0 +1,32 @@
+pub mod assets;
+pub mod blit;
+pub mod camera;
+pub mod canvas;
+pub mod collision;
+pub mod color;
+pub mod components;
+pub mod debug_overlay;
+pub mod dpi;
+pub mod ecs;
+pub mod error;
+pub mod font;
+pub mod framebuffer;
+pub mod golden;
+pub mod graphics;
+pub mod hierarchy;
+pub mod image;
+pub mod input;
+pub mod inspector;
+pub mod logging;
+pub mod math;
+pub mod physics;
+pub mod ppm;
+pub mod presenter;
+pub mod raster;
+pub mod rect;
+pub mod scene;
+pub mod sound;
+pub mod systems;
+pub mod text;
+pub mod timestep;
+pub mod transform;
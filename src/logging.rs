@@ -0,0 +1,463 @@
+//! Leveled logging filtered per module, stamped with the frame number,
+//! with an optional file sink and a ring buffer of recent records.
+//!
+//! Log through the [`log!`](crate::log) macro or its per-level shorthands
+//! such as [`info!`](crate::info). Until [`init`] installs a logger, one
+//! configured by [`Logger::from_env`] is used.
+
+use std::collections::VecDeque;
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+
+/// Filter spec, e.g. `warn,window_app::physics=debug`.
+pub const LOG_ENV: &str = "WINDOW_APP_LOG";
+/// File that records are appended to in addition to stderr.
+pub const LOG_FILE_ENV: &str = "WINDOW_APP_LOG_FILE";
+/// Number of records kept by a logger's [`EventLog`].
+pub const HISTORY_LEN: usize = 64;
+
+/// Severity of a record, from most to least important.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for Level {
+    type Err = FilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => Err(FilterError(s.trim().to_owned())),
+        }
+    }
+}
+
+/// A directive in a filter spec that isn't a level or `module=level`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FilterError(String);
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid log directive {:?}", self.0)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+/// Most verbose level logged per module, falling back to a default.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter {
+    default: Level,
+    modules: Vec<(String, Level)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new(Level::Info)
+    }
+}
+
+impl Filter {
+    pub fn new(default: Level) -> Self {
+        Self { default, modules: Vec::new() }
+    }
+
+    /// Logs `module` and its submodules up to `level`.
+    pub fn with_module(mut self, module: &str, level: Level) -> Self {
+        self.modules
+            .retain(|(name, _)| name != module);
+        self.modules
+            .push((module.to_owned(), level));
+        self
+    }
+
+    /// Parses comma-separated directives, each a level for every module
+    /// or `module=level`.
+    pub fn parse(spec: &str) -> Result<Self, FilterError> {
+        let mut filter = Self::default();
+        for directive in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+        {
+            filter = match directive.split_once('=') {
+                Some((module, level)) => {
+                    filter.with_module(module.trim(), level.parse()?)
+                }
+                None => Self { default: directive.parse()?, ..filter },
+            };
+        }
+        Ok(filter)
+    }
+
+    /// Level for `module`, taken from the longest configured prefix.
+    pub fn level_for(&self, module: &str) -> Level {
+        self.modules
+            .iter()
+            .filter(|(name, _)| {
+                module
+                    .strip_prefix(name.as_str())
+                    .is_some_and(|rest| {
+                        rest.is_empty() || rest.starts_with("::")
+                    })
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    pub fn enabled(&self, level: Level, module: &str) -> bool {
+        level <= self.level_for(module)
+    }
+}
+
+/// One logged message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub frame: u64,
+    pub level: Level,
+    pub module: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{:>6} {:<5} {}] {}",
+            self.frame, self.level, self.module, self.message
+        )
+    }
+}
+
+/// The most recent records, dropping the oldest once full.
+#[derive(Clone, Debug, Default)]
+pub struct EventLog {
+    records: VecDeque<Record>,
+    capacity: usize,
+}
+
+impl EventLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, record: Record) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Records oldest first.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Record> + '_ {
+        self.records.iter()
+    }
+
+    /// Up to the last `count` records, oldest first.
+    pub fn last(&self, count: usize) -> impl Iterator<Item = &Record> + '_ {
+        self.records
+            .iter()
+            .skip(self.records.len().saturating_sub(count))
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+/// Filters records, writes them to stderr and an optional file, and
+/// keeps the recent ones.
+#[derive(Debug)]
+pub struct Logger {
+    filter: Filter,
+    frame: u64,
+    stderr: bool,
+    file: Option<LineWriter<File>>,
+    history: EventLog,
+}
+
+impl Logger {
+    pub fn new(filter: Filter) -> Self {
+        Self {
+            filter,
+            frame: 0,
+            stderr: true,
+            file: None,
+            history: EventLog::new(HISTORY_LEN),
+        }
+    }
+
+    /// A logger filtered by [`LOG_ENV`] and writing to [`LOG_FILE_ENV`]
+    /// when set. A bad spec or file is reported and skipped.
+    pub fn from_env() -> Self {
+        let spec = env::var(LOG_ENV).unwrap_or_default();
+        let filter = Filter::parse(&spec);
+        let mut logger = Self::new(filter.clone().unwrap_or_default());
+        if let Err(err) = filter {
+            logger.log(Level::Warn, module_path!(), format_args!("{err}"));
+        }
+        if let Ok(path) = env::var(LOG_FILE_ENV) {
+            match open_sink(&path) {
+                Ok(file) => logger.file = Some(file),
+                Err(err) => logger.log(
+                    Level::Warn,
+                    module_path!(),
+                    format_args!("failed to open log file {path}: {err}"),
+                ),
+            }
+        }
+        logger
+    }
+
+    /// Also appends records to the file at `path`.
+    pub fn with_file(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        self.file = Some(open_sink(path)?);
+        Ok(self)
+    }
+
+    /// Stops echoing records to stderr.
+    pub fn quiet(mut self) -> Self {
+        self.stderr = false;
+        self
+    }
+
+    pub fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Stamps later records with `frame`.
+    pub fn set_frame(&mut self, frame: u64) {
+        self.frame = frame;
+    }
+
+    pub fn history(&self) -> &EventLog {
+        &self.history
+    }
+
+    /// Records `args` if `level` is enabled for `module`. Arguments are
+    /// only formatted when they will be logged.
+    pub fn log(
+        &mut self,
+        level: Level,
+        module: &'static str,
+        args: fmt::Arguments<'_>,
+    ) {
+        if !self.filter.enabled(level, module) {
+            return;
+        }
+        let record = Record {
+            frame: self.frame,
+            level,
+            module,
+            message: args.to_string(),
+        };
+        if self.stderr {
+            eprintln!("{record}");
+        }
+        if let Some(file) = &mut self.file
+            && writeln!(file, "{record}").is_err()
+        {
+            self.file = None;
+        }
+        self.history.push(record);
+    }
+}
+
+fn open_sink(path: impl AsRef<Path>) -> io::Result<LineWriter<File>> {
+    let file = File::options()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    Ok(LineWriter::new(file))
+}
+
+static LOGGER: Mutex<Option<Logger>> = Mutex::new(None);
+
+fn with_logger<R>(f: impl FnOnce(&mut Logger) -> R) -> R {
+    let mut logger = LOGGER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    f(logger.get_or_insert_with(Logger::from_env))
+}
+
+/// Replaces the global logger.
+pub fn init(logger: Logger) {
+    *LOGGER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(logger);
+}
+
+/// Logs through the global logger; used by the macros.
+pub fn log(level: Level, module: &'static str, args: fmt::Arguments<'_>) {
+    with_logger(|logger| logger.log(level, module, args));
+}
+
+/// Whether the global logger would keep a `level` record from `module`.
+pub fn enabled(level: Level, module: &str) -> bool {
+    with_logger(|logger| logger.filter().enabled(level, module))
+}
+
+/// Stamps later global records with `frame`.
+pub fn set_frame(frame: u64) {
+    with_logger(|logger| logger.set_frame(frame));
+}
+
+/// The global logger's last `count` records, oldest first.
+pub fn recent(count: usize) -> Vec<Record> {
+    with_logger(|logger| {
+        logger
+            .history()
+            .last(count)
+            .cloned()
+            .collect()
+    })
+}
+
+/// Logs a formatted message at a [`Level`] from the calling module.
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        $crate::logging::log($level, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::logging::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::logging::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::logging::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::logging::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::logging::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn filters_by_longest_module_prefix() {
+        let filter =
+            Filter::parse("warn, window_app::physics=debug,window_app=info")
+                .unwrap();
+
+        assert_eq!(filter.level_for("main"), Level::Warn);
+        assert_eq!(filter.level_for("window_app"), Level::Info);
+        assert_eq!(filter.level_for("window_app::graphics"), Level::Info);
+        assert_eq!(filter.level_for("window_app::physics"), Level::Debug);
+        assert_eq!(filter.level_for("window_app_other"), Level::Warn);
+        assert!(filter.enabled(Level::Error, "main"));
+        assert!(!filter.enabled(Level::Info, "main"));
+        assert_eq!(
+            Filter::parse("window_app=loud"),
+            Err(FilterError("loud".into()))
+        );
+    }
+
+    #[test]
+    fn event_log_keeps_the_newest_records() {
+        let mut log = EventLog::new(3);
+        for frame in 0..5 {
+            log.push(Record {
+                frame,
+                level: Level::Info,
+                module: "test",
+                message: String::new(),
+            });
+        }
+
+        let frames: Vec<_> = log.iter().map(|r| r.frame).collect();
+        assert_eq!(frames, [2, 3, 4]);
+        let last: Vec<_> = log.last(2).map(|r| r.frame).collect();
+        assert_eq!(last, [3, 4]);
+    }
+
+    #[test]
+    fn stamps_frames_and_writes_the_file_sink() {
+        let path = env::temp_dir().join("window_app_logging_test.log");
+        let _ = fs::remove_file(&path);
+        let mut logger = Logger::new(Filter::new(Level::Info))
+            .quiet()
+            .with_file(&path)
+            .unwrap();
+
+        logger.log(Level::Debug, "game", format_args!("hidden"));
+        logger.set_frame(42);
+        logger.log(Level::Warn, "game", format_args!("low on {}", "fuel"));
+
+        // The global logger is never dropped, so records can't wait for it.
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[    42 WARN  game] low on fuel\n"
+        );
+        drop(logger);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -1,263 +1,699 @@
-use std::num::NonZeroU32;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 use winit::application::ApplicationHandler;
 use winit::dpi::LogicalSize;
-use winit::event::{MouseButton, WindowEvent};
-use winit::event_loop::{ActiveEventLoop, EventLoop};
-use winit::keyboard::{KeyCode, PhysicalKey};
-use winit::window::{Window, WindowId};
-
-use softbuffer::{Context, Surface};
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy};
+use winit::window::{Window, WindowAttributes, WindowId};
+
+use window_app::assets::{AssetEvent, AssetWatcher, Handle};
+use window_app::canvas::{ScaleMode, VirtualCanvas};
+use window_app::debug_overlay::{DebugInfo, DebugOverlay};
+use window_app::error::Error;
+use window_app::framebuffer::Framebuffer;
+use window_app::graphics::GraphicsState;
+use window_app::image::Image;
+use window_app::input::{
+    InputEvent, InputMap, InputRecorder, InputRecording, actions,
+};
+use window_app::inspector::Inspector;
+use window_app::logging;
+use window_app::ppm;
+use window_app::presenter::SurfacePresenter;
+use window_app::scene::Scene;
+use window_app::timestep::FixedTimestep;
+use window_app::{debug, error, info, trace, warn};
+
+const TICK_RATE: f64 = 60.0;
+const MAX_CATCH_UP_STEPS: u32 = 5;
+const SQUARE_SPRITE_PATH: &str = "assets/square.png";
+const BINDINGS_PATH: &str = "assets/bindings.ron";
+/// Most recent log records shown under the debug overlay.
+const OVERLAY_LOG_LINES: usize = 6;
+/// Logical resolution to render at, or `None` to render at the window's.
+const CANVAS: Option<VirtualCanvas> =
+    Some(VirtualCanvas::new(320, 180, ScaleMode::Integer));
+
+/// Sent to the event loop from other threads.
+#[derive(Debug)]
+enum AppEvent {
+    /// A watched asset file changed on disk.
+    AssetChanged(PathBuf),
+}
 
+/// Every open window, keyed by id so events reach the right one.
 struct App {
-    gfx_state: Option<GraphicsState>,
+    windows: HashMap<WindowId, AppWindow>,
+    /// Reports changes to loaded assets, or `None` if watching failed.
+    watcher: Option<AssetWatcher>,
+    /// Where to save the session's input on exit, when recording.
+    record_path: Option<PathBuf>,
+    /// Scene game views start with instead of the lone square.
+    scene_path: Option<PathBuf>,
+    /// What stopped the event loop, for `main` to report.
+    error: Option<Error>,
 }
 
-impl ApplicationHandler for App {
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        let window = event_loop
-            .create_window(
-                Window::default_attributes()
-                    .with_title("Window App")
-                    .with_inner_size(LogicalSize::new(800.0, 600.0)),
-            )
-            .expect("failed to create window");
+struct AppWindow {
+    presenter: SurfacePresenter,
+    view: View,
+}
+
+/// What a window shows.
+enum View {
+    Game(Box<GameView>),
+    /// Entity listing for the game view in window `target`.
+    Inspector {
+        inspector: Inspector,
+        target: WindowId,
+        screen: Framebuffer,
+    },
+}
+
+/// A running scene with its own clock, overlay and optional recording.
+struct GameView {
+    state: GraphicsState,
+    timestep: FixedTimestep,
+    last_time_frame: Instant,
+    debug_overlay: DebugOverlay,
+    /// Window-sized frame the canvas is scaled into.
+    screen: Framebuffer,
+    recorder: Option<InputRecorder>,
+    inspector: Option<WindowId>,
+    /// Scene the view was started with, respawned when its file changes.
+    scene: Option<SceneAsset>,
+    /// Bindings file, reapplied when it changes.
+    bindings: Option<Handle<InputMap>>,
+    /// Frames drawn so far, stamped on log records.
+    frame: u64,
+}
 
-        let state = GraphicsState::new(window);
-        state.window.request_redraw();
-        self.gfx_state = Some(state);
+struct SceneAsset {
+    handle: Handle<Scene>,
+    /// Directory sprite paths are relative to.
+    base_dir: PathBuf,
+}
+
+impl App {
+    fn open_window(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        attributes: WindowAttributes,
+        view: impl FnOnce(&SurfacePresenter) -> Result<View, Error>,
+    ) -> Result<WindowId, Error> {
+        let window = event_loop.create_window(attributes)?;
+        let presenter = SurfacePresenter::new(window)?;
+        let view = view(&presenter)?;
+        let id = presenter.window().id();
+        presenter.window().request_redraw();
+        self.windows
+            .insert(id, AppWindow { presenter, view });
+        debug!("Opened window {id:?}");
+        Ok(id)
     }
 
-    fn window_event(
+    fn open_game(
         &mut self,
         event_loop: &ActiveEventLoop,
-        window_id: WindowId,
-        event: WindowEvent,
-    ) {
-        let Some(gfx_state) = self.gfx_state.as_mut() else {
+    ) -> Result<WindowId, Error> {
+        let record = self.record_path.is_some();
+        let scene_path = self.scene_path.clone();
+        let id = self.open_window(
+            event_loop,
+            Window::default_attributes()
+                .with_title("Window App")
+                .with_inner_size(LogicalSize::new(800.0, 600.0)),
+            |presenter| {
+                let game =
+                    GameView::new(presenter, record, scene_path.as_deref())?;
+                Ok(View::Game(Box::new(game)))
+            },
+        )?;
+        self.watch_assets();
+        Ok(id)
+    }
+
+    /// Watches every file the game views have loaded.
+    fn watch_assets(&mut self) {
+        let Some(watcher) = &mut self.watcher else {
             return;
         };
-        if gfx_state.window.id() != window_id {
-            return;
+        for window in self.windows.values() {
+            let View::Game(game) = &window.view else {
+                continue;
+            };
+            for path in game.state.assets().files() {
+                if let Err(err) = watcher.watch(path) {
+                    warn!("Not watching {}: {err}", path.display());
+                }
+            }
         }
+    }
 
-        match event {
-            WindowEvent::CloseRequested => {
-                event_loop.exit();
-            }
-            WindowEvent::RedrawRequested => {
-                gfx_state.render();
-            }
-            WindowEvent::Resized(_) => {
-                gfx_state.window.request_redraw();
-            }
-            WindowEvent::KeyboardInput { event, .. } => {
-                if event.state.is_pressed() {
-                    match event.physical_key {
-                        PhysicalKey::Code(KeyCode::KeyW) => {
-                            gfx_state.square_pos_y -= 20.0;
-                            gfx_state.square_pos_y =
-                                gfx_state.square_pos_y.max(0.0);
-                            gfx_state.window.request_redraw();
-                        }
-                        PhysicalKey::Code(KeyCode::KeyA) => {
-                            gfx_state.square_pos_x -= 20.0;
-                            gfx_state.square_pos_x =
-                                gfx_state.square_pos_x.max(0.0);
-                            gfx_state.window.request_redraw();
-                        }
-                        PhysicalKey::Code(KeyCode::KeyS) => {
-                            let (_, max_pos_y) = gfx_state.max_square_pos();
-
-                            gfx_state.square_pos_y += 20.0;
-                            gfx_state.square_pos_y =
-                                gfx_state.square_pos_y.min(max_pos_y);
-                            gfx_state.window.request_redraw();
-                        }
-                        PhysicalKey::Code(KeyCode::KeyD) => {
-                            let (max_pos_x, _) = gfx_state.max_square_pos();
-
-                            gfx_state.square_pos_x += 20.0;
-                            gfx_state.square_pos_x =
-                                gfx_state.square_pos_x.min(max_pos_x);
-                            gfx_state.window.request_redraw();
-                        }
-                        _ => {}
+    fn open_inspector(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        target: WindowId,
+    ) -> Result<WindowId, Error> {
+        let id = self.open_window(
+            event_loop,
+            Window::default_attributes()
+                .with_title("Inspector")
+                .with_inner_size(LogicalSize::new(480.0, 360.0)),
+            |_| {
+                Ok(View::Inspector {
+                    inspector: Inspector::new(),
+                    target,
+                    screen: Framebuffer::new(0, 0),
+                })
+            },
+        )?;
+        if let Some(View::Game(game)) = self
+            .windows
+            .get_mut(&target)
+            .map(|w| &mut w.view)
+        {
+            game.inspector = Some(id);
+        }
+        Ok(id)
+    }
+
+    /// Closes window `id` along with any inspectors of it, exiting once
+    /// no windows are left.
+    fn close_window(&mut self, event_loop: &ActiveEventLoop, id: WindowId) {
+        let Some(window) = self.windows.remove(&id) else {
+            return;
+        };
+        debug!("Closed window {id:?}");
+        match window.view {
+            View::Game(mut game) => {
+                if let (Some(recorder), Some(path)) =
+                    (game.recorder.take(), &self.record_path)
+                {
+                    match recorder.finish().save(path) {
+                        Ok(()) => info!("Saved input to {}", path.display()),
+                        Err(err) => error!("Failed to save input: {err}"),
                     }
                 }
+                if let Some(inspector) = game.inspector {
+                    self.windows.remove(&inspector);
+                }
             }
-            WindowEvent::MouseInput { button, state, .. } => {
-                if state.is_pressed() {
-                    match button {
-                        MouseButton::Left => {
-                            let x = gfx_state.cursor_x as usize;
-                            let y = gfx_state.cursor_y as usize;
-
-                            let size = gfx_state.window.inner_size();
-                            let w = NonZeroU32::new(size.width.max(1)).unwrap();
-                            let h =
-                                NonZeroU32::new(size.height.max(1)).unwrap();
-
-                            let width = w.get() as usize;
-                            let height = h.get() as usize;
-
-                            let mw = width * 10 / 100;
-                            let mh = height * 10 / 100;
-
-                            gfx_state.square_pos_x =
-                                (x as f64) - (mw as f64 / 2.0);
-                            gfx_state.square_pos_y =
-                                (y as f64) - (mh as f64 / 2.0);
-                            gfx_state.window.request_redraw();
-                        }
-                        _ => {}
-                    }
+            View::Inspector { target, .. } => {
+                if let Some(View::Game(game)) = self
+                    .windows
+                    .get_mut(&target)
+                    .map(|w| &mut w.view)
+                {
+                    game.inspector = None;
                 }
             }
-            WindowEvent::CursorMoved { position, .. } => {
-                gfx_state.cursor_x = position.x;
-                gfx_state.cursor_y = position.y;
+        }
+        if self.windows.is_empty() {
+            event_loop.exit();
+        }
+    }
+
+    fn toggle_inspector(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        target: WindowId,
+    ) {
+        let Some(View::Game(game)) = self
+            .windows
+            .get(&target)
+            .map(|w| &w.view)
+        else {
+            return;
+        };
+        match game.inspector {
+            Some(inspector) => self.close_window(event_loop, inspector),
+            None => {
+                // The game runs fine without its inspector.
+                if let Err(err) = self.open_inspector(event_loop, target) {
+                    error!("Failed to open inspector: {err}");
+                }
             }
-            _ => {
-                println!("Got event: {:?}", event);
-            } // ignore all other events
         }
     }
-}
 
-struct GraphicsState {
-    window: Arc<Window>,
-    _context: Context<Arc<Window>>,
-    surface: Surface<Arc<Window>, Arc<Window>>,
-    square_pos_x: f64,
-    square_pos_y: f64,
-    velocity_x: f64,
-    velocity_y: f64,
-    last_time_frame: Instant,
-    cursor_x: f64,
-    cursor_y: f64,
+    fn draw_inspector(
+        &mut self,
+        id: WindowId,
+        target: WindowId,
+    ) -> Result<(), Error> {
+        let [Some(window), Some(game)] = self
+            .windows
+            .get_disjoint_mut([&id, &target])
+        else {
+            return Ok(());
+        };
+        let (View::Inspector { inspector, screen, .. }, View::Game(game)) =
+            (&mut window.view, &game.view)
+        else {
+            return Ok(());
+        };
+        let (width, height) = window.presenter.size();
+        screen.resize(width, height);
+        inspector.draw(
+            &game.state.world,
+            screen,
+            window.presenter.window().scale_factor(),
+        );
+        window.presenter.present(screen)?;
+        window
+            .presenter
+            .window()
+            .request_redraw();
+        Ok(())
+    }
+
+    /// Records `err` for `main` and shuts down, still closing each
+    /// window so recordings get saved.
+    fn fail(&mut self, event_loop: &ActiveEventLoop, err: Error) {
+        self.error.get_or_insert(err);
+        let ids: Vec<_> = self.windows.keys().copied().collect();
+        for id in ids {
+            self.close_window(event_loop, id);
+        }
+        event_loop.exit();
+    }
 }
 
-impl GraphicsState {
-    fn new(window: Window) -> Self {
-        let window = Arc::new(window);
-        let context =
-            Context::new(window.clone()).expect("failed to create context");
-        let surface = Surface::new(&context, window.clone())
-            .expect("failed to create window surface");
-
-        let size = window.inner_size();
-        let w = NonZeroU32::new(size.width.max(1)).unwrap();
-        let h = NonZeroU32::new(size.height.max(1)).unwrap();
-
-        let width = w.get() as usize;
-        let height = h.get() as usize;
-
-        let mw = width * 10 / 100;
-        let mh = height * 10 / 100;
-
-        let square_pos_x = (width / 2 - mw / 2) as f64;
-        let square_pos_y = (height / 2 - mh / 2) as f64;
-
-        Self {
-            window,
-            _context: context,
-            surface,
-            square_pos_x: square_pos_x,
-            square_pos_y: square_pos_y,
-            velocity_x: 100.0,
-            velocity_y: 100.0,
+impl GameView {
+    fn new(
+        presenter: &SurfacePresenter,
+        record: bool,
+        scene_path: Option<&Path>,
+    ) -> Result<Self, Error> {
+        let (width, height) = presenter.size();
+        let scale_factor = presenter.window().scale_factor();
+        let mut state = match CANVAS {
+            Some(canvas) => {
+                let mut state =
+                    GraphicsState::with_canvas(canvas, width, height);
+                state.set_scale_factor(scale_factor);
+                state
+            }
+            None => GraphicsState::for_window(width, height, scale_factor),
+        };
+        // Drawn solid until the sprite finishes loading.
+        let sprite = state
+            .assets_mut()
+            .load(SQUARE_SPRITE_PATH);
+        state.square_sprite = Some(sprite);
+        let scene = match scene_path {
+            Some(path) => {
+                let handle = state
+                    .assets_mut()
+                    .load_blocking::<Scene>(path)?;
+                let scene = SceneAsset {
+                    handle,
+                    base_dir: path
+                        .parent()
+                        .unwrap_or(Path::new(""))
+                        .to_owned(),
+                };
+                scene.spawn(&mut state)?;
+                info!("Loaded scene {}", path.display());
+                Some(scene)
+            }
+            None => None,
+        };
+        let loaded = state
+            .assets_mut()
+            .load_blocking::<InputMap>(BINDINGS_PATH);
+        let bindings = match loaded {
+            Ok(handle) => {
+                let map = state.assets().get(&handle).cloned();
+                state.set_input_map(map.expect("handle was just loaded"));
+                Some(handle)
+            }
+            Err(err) => {
+                warn!("Using built-in bindings: {err}");
+                None
+            }
+        };
+        let recorder = record.then(|| {
+            InputRecorder::new(
+                TICK_RATE,
+                width as u32,
+                height as u32,
+                state.input_map().clone(),
+            )
+            .with_scale_factor(scale_factor)
+            .with_canvas(CANVAS)
+        });
+
+        Ok(Self {
+            state,
+            timestep: FixedTimestep::new(TICK_RATE)
+                .with_max_steps(MAX_CATCH_UP_STEPS),
             last_time_frame: Instant::now(),
-            cursor_x: 0.0,
-            cursor_y: 0.0,
+            debug_overlay: DebugOverlay::new(),
+            screen: Framebuffer::new(0, 0),
+            recorder,
+            inspector: None,
+            scene,
+            bindings,
+            frame: 0,
+        })
+    }
+
+    /// Reloads the assets read from `path`.
+    fn asset_changed(&mut self, path: &Path) {
+        let events = self.state.reload_asset(path);
+        self.apply_asset_events(events);
+    }
+
+    /// Applies loaded and reloaded assets that the running scene doesn't
+    /// pick up by itself, except while recording. Files that fail to load
+    /// leave the view as it was.
+    fn apply_asset_events(&mut self, events: Vec<AssetEvent>) {
+        for event in events {
+            let handle = match event {
+                AssetEvent::Loaded { handle, path } => {
+                    debug!("Loaded {}", path.display());
+                    handle
+                }
+                AssetEvent::Reloaded { handle, path } => {
+                    info!("Reloaded {}", path.display());
+                    handle
+                }
+                AssetEvent::Failed { path, error, .. } => {
+                    warn!("Failed to load {}: {error}", path.display());
+                    continue;
+                }
+            };
+            // A recording only replays the bindings and scene it started
+            // with, so they stay put until it's saved.
+            if self.recorder.is_some() {
+                if self
+                    .scene
+                    .as_ref()
+                    .is_some_and(|s| handle == s.handle)
+                    || self
+                        .bindings
+                        .as_ref()
+                        .is_some_and(|b| handle == *b)
+                {
+                    warn!("Not applying reload while recording");
+                }
+                continue;
+            }
+            if let Some(scene) = &self.scene
+                && handle == scene.handle
+                && let Err(err) = scene.spawn(&mut self.state)
+            {
+                warn!("Keeping old scene: {err}");
+            }
+            if let Some(bindings) = &self.bindings
+                && handle == *bindings
+            {
+                let map = self
+                    .state
+                    .assets()
+                    .get(bindings)
+                    .cloned();
+                if let Some(map) = map {
+                    self.state.set_input_map(map);
+                }
+            }
+        }
+    }
+
+    /// Handles one event for this view's window, returning whether it
+    /// asked to toggle the inspector, which only the app can open.
+    fn window_event(
+        &mut self,
+        presenter: &mut SurfacePresenter,
+        event: WindowEvent,
+    ) -> Result<bool, Error> {
+        let input = InputEvent::from_window_event(&event);
+        if let Some(input) = input {
+            if let Some(recorder) = &mut self.recorder {
+                recorder.record(input);
+            }
+            self.state.apply_input(&input);
+        }
+
+        match event {
+            WindowEvent::RedrawRequested => {
+                self.redraw(presenter)?;
+            }
+            WindowEvent::Resized(_) => {
+                presenter.window().request_redraw();
+            }
+            WindowEvent::KeyboardInput { event, .. } if !event.repeat => {
+                let Some(InputEvent::Press(button)) = input else {
+                    return Ok(false);
+                };
+                let modifiers = self.state.input().modifiers();
+                let mut toggle_inspector = false;
+                for action in self
+                    .state
+                    .input_map()
+                    .actions_for(button, modifiers)
+                {
+                    match action {
+                        actions::TOGGLE_DEBUG => self.debug_overlay.toggle(),
+                        actions::TOGGLE_INSPECTOR => toggle_inspector = true,
+                        _ => {}
+                    }
+                }
+                return Ok(toggle_inspector);
+            }
+            _ if input.is_some() => {}
+            _ => trace!("Unhandled event: {event:?}"),
         }
+        Ok(false)
     }
 
-    fn render(&mut self) {
-        let dt = self
+    fn redraw(
+        &mut self,
+        presenter: &mut SurfacePresenter,
+    ) -> Result<(), Error> {
+        let frame_time = self
             .last_time_frame
             .elapsed()
             .as_secs_f64();
         self.last_time_frame = Instant::now();
+        self.frame += 1;
+        logging::set_frame(self.frame);
+        let events = self.state.update_assets();
+        self.apply_asset_events(events);
+        self.debug_overlay
+            .stats
+            .record(frame_time);
+
+        for _ in 0..self.timestep.advance(frame_time) {
+            self.state.update(self.timestep.dt());
+            if let Some(recorder) = &mut self.recorder {
+                recorder.end_tick();
+            }
+        }
+        self.state.render(self.timestep.alpha());
+        let (width, height) = self.state.window_size();
+        let (pos, vel) =
+            (self.state.square_position(), self.state.square_velocity());
+        let info = DebugInfo {
+            window_size: (width as u32, height as u32),
+            scale_factor: self.state.scale_factor(),
+            square_position: (pos.x, pos.y),
+            square_velocity: (vel.x, vel.y),
+        };
+        let frame = match self.state.canvas() {
+            Some(canvas) => {
+                self.screen.resize(width, height);
+                canvas.present(&self.state.framebuffer, &mut self.screen);
+                &mut self.screen
+            }
+            None => &mut self.state.framebuffer,
+        };
+        self.debug_overlay.draw(frame, &info);
+        self.debug_overlay.draw_log(
+            frame,
+            &logging::recent(OVERLAY_LOG_LINES),
+            info.scale_factor,
+        );
+        presenter.present(frame)?;
+        presenter.window().request_redraw();
+        Ok(())
+    }
+}
 
-        let (max_pos_x, max_pos_y) = self.max_square_pos();
-
-        let size = self.window.inner_size();
-        let w = NonZeroU32::new(size.width.max(1)).unwrap();
-        let h = NonZeroU32::new(size.height.max(1)).unwrap();
-
-        self.surface
-            .resize(w, h)
-            .expect("resize failed");
-
-        let mut buffer = self
-            .surface
-            .buffer_mut()
-            .expect("buffer failed");
-        buffer.fill(0x00202020);
-
-        let width = w.get() as usize;
-        let height = h.get() as usize;
-
-        let mw = width * 10 / 100;
-        let mh = height * 10 / 100;
-
-        self.square_pos_x = self.square_pos_x + (self.velocity_x * dt);
-        self.square_pos_y = self.square_pos_y + (self.velocity_y * dt);
-
-        let square_start_x = (self.square_pos_x as usize).min(width);
-        let square_end_x = (square_start_x + mw).min(width);
+impl SceneAsset {
+    /// Replaces the view's entities with the scene's current version.
+    fn spawn(&self, state: &mut GraphicsState) -> Result<(), Error> {
+        let scene = state
+            .assets()
+            .get(&self.handle)
+            .cloned();
+        let scene = scene.expect("scene handles come from the same assets");
+        state.load_scene(&scene, &self.base_dir)?;
+        Ok(())
+    }
+}
 
-        let square_start_y = (self.square_pos_y as usize).min(height);
-        let square_end_y = (square_start_y + mh).min(height);
+impl ApplicationHandler<AppEvent> for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.windows.is_empty()
+            && let Err(err) = self.open_game(event_loop)
+        {
+            self.fail(event_loop, err);
+        }
+    }
 
-        if square_end_x >= width || square_start_x <= 0 {
-            self.velocity_x = -self.velocity_x;
-            self.square_pos_x = self.square_pos_x.clamp(0.0, max_pos_x);
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        if let WindowEvent::CloseRequested = event {
+            self.close_window(event_loop, window_id);
+            return;
         }
-        if square_end_y >= height || square_start_y <= 0 {
-            self.velocity_y = -self.velocity_y;
-            self.square_pos_y = self.square_pos_y.clamp(0.0, max_pos_y);
+        let Some(window) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+
+        let result = match &mut window.view {
+            View::Game(game) => game
+                .window_event(&mut window.presenter, event)
+                .map(|toggle_inspector| {
+                    if toggle_inspector {
+                        self.toggle_inspector(event_loop, window_id);
+                    }
+                }),
+            View::Inspector { target, .. } => match event {
+                WindowEvent::RedrawRequested => {
+                    let target = *target;
+                    // A broken inspector shouldn't take the game down.
+                    if let Err(err) = self.draw_inspector(window_id, target) {
+                        warn!("Closing inspector: {err}");
+                        self.close_window(event_loop, window_id);
+                    }
+                    Ok(())
+                }
+                WindowEvent::Resized(_) => {
+                    window
+                        .presenter
+                        .window()
+                        .request_redraw();
+                    Ok(())
+                }
+                _ => Ok(()),
+            },
+        };
+        if let Err(err) = result {
+            self.fail(event_loop, err);
         }
+    }
 
-        for y in square_start_y..square_end_y {
-            for x in square_start_x..square_end_x {
-                buffer[y * width + x] = 0x00FF00FF;
+    fn user_event(&mut self, _: &ActiveEventLoop, event: AppEvent) {
+        match event {
+            AppEvent::AssetChanged(path) => {
+                debug!("Changed on disk: {}", path.display());
+                for window in self.windows.values_mut() {
+                    if let View::Game(game) = &mut window.view {
+                        game.asset_changed(&path);
+                    }
+                }
+                // Reloaded scenes may reference new sprites.
+                self.watch_assets();
             }
         }
-
-        buffer
-            .present()
-            .expect("present failed");
-
-        self.window.request_redraw();
     }
+}
 
-    fn max_square_pos(&self) -> (f64, f64) {
-        let size = self.window.inner_size();
-        let w = NonZeroU32::new(size.width.max(1)).unwrap();
-        let h = NonZeroU32::new(size.height.max(1)).unwrap();
-
-        let width = w.get() as f64;
-        let height = h.get() as f64;
-
-        let mw = width * 0.1;
-        let mh = height * 0.1;
+/// Watches assets, forwarding changes to the event loop.
+fn asset_watcher(proxy: EventLoopProxy<AppEvent>) -> Option<AssetWatcher> {
+    let watcher = AssetWatcher::new(move |path| {
+        // Fails only once the event loop has exited.
+        let _ = proxy.send_event(AppEvent::AssetChanged(path));
+    });
+    match watcher {
+        Ok(watcher) => Some(watcher),
+        Err(err) => {
+            warn!("Assets won't reload: {err}");
+            None
+        }
+    }
+}
 
-        let max_pos_x = width - mw;
-        let max_pos_y = height - mh;
+fn load_sprite(state: &mut GraphicsState) {
+    let sprite = state
+        .assets_mut()
+        .load_blocking::<Image>(SQUARE_SPRITE_PATH);
+    match sprite {
+        Ok(sprite) => state.square_sprite = Some(sprite),
+        Err(err) => warn!("Using solid square: {err}"),
+    }
+}
 
-        return (max_pos_x, max_pos_y);
+/// Reruns a recording without a window, optionally saving the last frame.
+fn replay(recording_path: &str, frame_path: Option<&str>) -> Result<(), Error> {
+    let recording = InputRecording::load(recording_path)?;
+    let mut state = recording.initial_state();
+    load_sprite(&mut state);
+
+    recording.replay(&mut state);
+    let (pos, vel) = (state.square_position(), state.square_velocity());
+    println!(
+        "Replayed {} ticks: square at ({:.3}, {:.3}) moving ({:.3}, {:.3})",
+        recording.ticks, pos.x, pos.y, vel.x, vel.y
+    );
+
+    if let Some(frame_path) = frame_path {
+        state.render(1.0);
+        ppm::save(frame_path, &state.framebuffer)?;
     }
+    Ok(())
 }
 
+/// Usage: `window_app [--record <input.ron>]`,
+/// `window_app --scene <scene.ron|scene.json>` or
+/// `window_app --replay <input.ron> [<last_frame.ppm>]`.
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut app = App { gfx_state: None };
-    let event_loop = EventLoop::new()?;
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut record_path = None;
+    let mut scene_path = None;
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => {}
+        ["--record", path] => record_path = Some(PathBuf::from(path)),
+        ["--scene", path] => scene_path = Some(PathBuf::from(path)),
+        ["--replay", path] => return Ok(replay(path, None)?),
+        ["--replay", path, frame] => return Ok(replay(path, Some(frame))?),
+        _ => {
+            return Err("usage: window_app [--record <input.ron>] \
+                 | --scene <scene.ron|scene.json> \
+                 | --replay <input.ron> [<last_frame.ppm>]"
+                .into());
+        }
+    }
+
+    let event_loop = EventLoop::<AppEvent>::with_user_event().build()?;
+    let mut app = App {
+        windows: HashMap::new(),
+        watcher: asset_watcher(event_loop.create_proxy()),
+        record_path,
+        scene_path,
+        error: None,
+    };
 
     event_loop.run_app(&mut app)?;
 
-    Ok(())
+    match app.error {
+        Some(err) => Err(err.into()),
+        None => Ok(()),
+    }
 }
@@ -0,0 +1,764 @@
+//! Impulse-based dynamics for entities with a [`RigidBody`].
+//!
+//! [`step_physics`] applies gravity, resolves contacts with sequential
+//! impulses and pushes overlapping bodies apart. It runs before
+//! [`integrate_velocity`], which then moves every body; rotation is
+//! advanced here.
+//!
+//! Colliders without a [`RigidBody`] act as infinitely heavy and move
+//! only by their own [`Velocity`], so a player-driven entity can push
+//! bodies around. Contacts between two of them are left to
+//! [`collide_bodies`].
+//!
+//! [`integrate_velocity`]: crate::systems::integrate_velocity
+//! [`collide_bodies`]: crate::systems::collide_bodies
+
+use serde::{Deserialize, Serialize};
+
+use crate::collision::{Manifold, Shape, SpatialHash, collide};
+use crate::components::{
+    AngularVelocity, Collider, Position, RigidBody, Rotation, Velocity,
+};
+use crate::ecs::{Entity, World};
+use crate::math::Vec2;
+use crate::systems::Time;
+
+/// Tuning for [`step_physics`], read from the world as a resource.
+/// Omitted fields deserialize as the defaults.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsSettings {
+    /// World units per second squared; +y is down.
+    pub gravity: Vec2,
+    /// Solver passes over every contact per tick. More passes make
+    /// stacks stiffer.
+    pub iterations: usize,
+    /// Overlap in world units left alone so resting contacts stay touching.
+    pub slop: f64,
+    /// Fraction of the remaining overlap removed each tick.
+    pub correction: f64,
+    /// Impacts slower than this, in world units per second, don't bounce.
+    pub bounce_threshold: f64,
+    /// Bodies slower than `sleep_speed` (world units per second) and
+    /// `sleep_angular_speed` (radians per second) for `sleep_delay`
+    /// seconds fall asleep.
+    pub sleep_speed: f64,
+    pub sleep_angular_speed: f64,
+    pub sleep_delay: f64,
+}
+
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        Self {
+            gravity: Vec2::new(0.0, 980.0),
+            iterations: 10,
+            slop: 0.5,
+            correction: 0.4,
+            bounce_threshold: 30.0,
+            sleep_speed: 5.0,
+            sleep_angular_speed: 0.05,
+            sleep_delay: 0.5,
+        }
+    }
+}
+
+/// A collider's state for the duration of one step.
+struct Body {
+    entity: Entity,
+    /// Collider in world space at the start of the step.
+    shape: Shape,
+    position: Vec2,
+    rotation: f64,
+    velocity: Vec2,
+    angular_velocity: f64,
+    /// Inverse mass and inertia while awake.
+    inv_mass: f64,
+    inv_inertia: f64,
+    rigid: Option<RigidBody>,
+}
+
+impl Body {
+    /// Awake and dynamic, so impulses move it.
+    fn is_simulated(&self) -> bool {
+        self.rigid
+            .is_some_and(|rigid| rigid.is_dynamic() && !rigid.is_asleep())
+    }
+
+    fn inv_mass(&self) -> f64 {
+        if self.is_simulated() { self.inv_mass } else { 0.0 }
+    }
+
+    fn inv_inertia(&self) -> f64 {
+        if self.is_simulated() { self.inv_inertia } else { 0.0 }
+    }
+
+    fn velocity_at(&self, offset: Vec2) -> Vec2 {
+        self.velocity + offset.perp() * self.angular_velocity
+    }
+
+    fn apply_impulse(&mut self, impulse: Vec2, offset: Vec2) {
+        self.velocity += impulse * self.inv_mass();
+        self.angular_velocity += offset.cross(impulse) * self.inv_inertia();
+    }
+
+    fn is_moving(&self, settings: &PhysicsSettings) -> bool {
+        self.velocity.length() >= settings.sleep_speed
+            || self.angular_velocity.abs() >= settings.sleep_angular_speed
+    }
+}
+
+struct ContactPoint {
+    /// Offsets of the contact from each body's center.
+    offset_a: Vec2,
+    offset_b: Vec2,
+    normal_mass: f64,
+    tangent_mass: f64,
+    /// Separating speed the normal impulse aims for.
+    bounce: f64,
+    normal_impulse: f64,
+    tangent_impulse: f64,
+}
+
+struct Contact {
+    a: usize,
+    b: usize,
+    normal: Vec2,
+    depth: f64,
+    friction: f64,
+    points: Vec<ContactPoint>,
+    /// Solves both points' normal impulses together, so a box lands
+    /// flat instead of tipping towards whichever corner goes first.
+    block: Option<Block>,
+}
+
+/// Coupled effective mass of a two-point contact along the normal.
+struct Block {
+    k: [[f64; 2]; 2],
+    inverse: [[f64; 2]; 2],
+}
+
+impl Block {
+    /// Worst condition number before falling back to solving the
+    /// points one at a time.
+    const MAX_CONDITION: f64 = 1000.0;
+
+    fn new(
+        body_a: &Body,
+        body_b: &Body,
+        offsets: [(Vec2, Vec2); 2],
+        normal: Vec2,
+    ) -> Option<Self> {
+        let entry = |i: usize, j: usize| {
+            let ((a_i, b_i), (a_j, b_j)) = (offsets[i], offsets[j]);
+            body_a.inv_mass()
+                + body_b.inv_mass()
+                + body_a.inv_inertia() * a_i.cross(normal) * a_j.cross(normal)
+                + body_b.inv_inertia() * b_i.cross(normal) * b_j.cross(normal)
+        };
+        let k = [[entry(0, 0), entry(0, 1)], [entry(1, 0), entry(1, 1)]];
+        let det = k[0][0] * k[1][1] - k[0][1] * k[1][0];
+        if k[0][0] * k[0][0] >= Self::MAX_CONDITION * det {
+            return None;
+        }
+        let inverse =
+            [[k[1][1] / det, -k[0][1] / det], [-k[1][0] / det, k[0][0] / det]];
+        Some(Self { k, inverse })
+    }
+
+    /// Total impulses keeping both points from approaching, by trying
+    /// each combination of active points in turn.
+    fn solve(&self, impulses: [f64; 2], speeds: [f64; 2]) -> Option<[f64; 2]> {
+        let k = self.k;
+        let b = [
+            speeds[0] - (k[0][0] * impulses[0] + k[0][1] * impulses[1]),
+            speeds[1] - (k[1][0] * impulses[0] + k[1][1] * impulses[1]),
+        ];
+        let both = [
+            -(self.inverse[0][0] * b[0] + self.inverse[0][1] * b[1]),
+            -(self.inverse[1][0] * b[0] + self.inverse[1][1] * b[1]),
+        ];
+        let first = -b[0] / k[0][0];
+        let second = -b[1] / k[1][1];
+        [
+            (both, both[0] >= 0.0 && both[1] >= 0.0),
+            ([first, 0.0], first >= 0.0 && k[1][0] * first + b[1] >= 0.0),
+            ([0.0, second], second >= 0.0 && k[0][1] * second + b[0] >= 0.0),
+            ([0.0, 0.0], b[0] >= 0.0 && b[1] >= 0.0),
+        ]
+        .into_iter()
+        .find_map(|(x, valid)| valid.then_some(x))
+    }
+}
+
+impl Contact {
+    fn new(
+        bodies: &[Body],
+        (a, b): (usize, usize),
+        manifold: &Manifold,
+        settings: &PhysicsSettings,
+    ) -> Self {
+        let (body_a, body_b) = (&bodies[a], &bodies[b]);
+        let (rigid_a, rigid_b) = (
+            body_a
+                .rigid
+                .unwrap_or(RigidBody::fixed()),
+            body_b
+                .rigid
+                .unwrap_or(RigidBody::fixed()),
+        );
+        let restitution = rigid_a
+            .restitution
+            .max(rigid_b.restitution);
+        let normal = manifold.normal;
+        let tangent = normal.perp();
+
+        let effective_mass = |offset_a: Vec2, offset_b: Vec2, axis: Vec2| {
+            let k = body_a.inv_mass()
+                + body_b.inv_mass()
+                + body_a.inv_inertia() * offset_a.cross(axis).powi(2)
+                + body_b.inv_inertia() * offset_b.cross(axis).powi(2);
+            if k > 0.0 { 1.0 / k } else { 0.0 }
+        };
+        let points = manifold
+            .points()
+            .iter()
+            .map(|&point| {
+                let offset_a = point - body_a.position;
+                let offset_b = point - body_b.position;
+                let approach = (body_b.velocity_at(offset_b)
+                    - body_a.velocity_at(offset_a))
+                .dot(normal);
+                let bounce = if approach < -settings.bounce_threshold {
+                    -restitution * approach
+                } else {
+                    0.0
+                };
+                ContactPoint {
+                    offset_a,
+                    offset_b,
+                    normal_mass: effective_mass(offset_a, offset_b, normal),
+                    tangent_mass: effective_mass(offset_a, offset_b, tangent),
+                    bounce,
+                    normal_impulse: 0.0,
+                    tangent_impulse: 0.0,
+                }
+            })
+            .collect::<Vec<_>>();
+        let block = match points[..] {
+            [ref first, ref second] => Block::new(
+                body_a,
+                body_b,
+                [
+                    (first.offset_a, first.offset_b),
+                    (second.offset_a, second.offset_b),
+                ],
+                normal,
+            ),
+            _ => None,
+        };
+
+        Self {
+            a,
+            b,
+            normal,
+            depth: manifold.depth,
+            friction: (rigid_a.friction * rigid_b.friction).sqrt(),
+            points,
+            block,
+        }
+    }
+
+    /// One Gauss-Seidel pass: friction, then the non-penetration
+    /// impulse, each clamped against what was applied so far.
+    fn solve(&mut self, bodies: &mut [Body]) {
+        let tangent = self.normal.perp();
+        for index in 0..self.points.len() {
+            let point = &self.points[index];
+            let limit = self.friction * point.normal_impulse;
+            let delta = -self
+                .relative(bodies, point)
+                .dot(tangent)
+                * point.tangent_mass;
+            let total = (point.tangent_impulse + delta).clamp(-limit, limit);
+            let change = total - point.tangent_impulse;
+            self.points[index].tangent_impulse = total;
+            self.apply(bodies, index, tangent * change);
+        }
+
+        let block = self.block.as_ref().and_then(|block| {
+            let [first, second] = &self.points[..] else {
+                return None;
+            };
+            block.solve(
+                [first.normal_impulse, second.normal_impulse],
+                [
+                    self.separating_speed(bodies, first),
+                    self.separating_speed(bodies, second),
+                ],
+            )
+        });
+        if let Some(totals) = block {
+            for (index, total) in totals.into_iter().enumerate() {
+                let change = total - self.points[index].normal_impulse;
+                self.points[index].normal_impulse = total;
+                self.apply(bodies, index, self.normal * change);
+            }
+            return;
+        }
+
+        for index in 0..self.points.len() {
+            let point = &self.points[index];
+            let delta =
+                -self.separating_speed(bodies, point) * point.normal_mass;
+            let total = (point.normal_impulse + delta).max(0.0);
+            let change = total - point.normal_impulse;
+            self.points[index].normal_impulse = total;
+            self.apply(bodies, index, self.normal * change);
+        }
+    }
+
+    fn relative(&self, bodies: &[Body], point: &ContactPoint) -> Vec2 {
+        bodies[self.b].velocity_at(point.offset_b)
+            - bodies[self.a].velocity_at(point.offset_a)
+    }
+
+    /// Normal speed beyond the bounce target; negative while the
+    /// point still needs pushing apart.
+    fn separating_speed(&self, bodies: &[Body], point: &ContactPoint) -> f64 {
+        self.relative(bodies, point)
+            .dot(self.normal)
+            - point.bounce
+    }
+
+    /// Pushes `b` by `impulse` at the point, and `a` the opposite way.
+    fn apply(&self, bodies: &mut [Body], index: usize, impulse: Vec2) {
+        let point = &self.points[index];
+        bodies[self.a].apply_impulse(-impulse, point.offset_a);
+        bodies[self.b].apply_impulse(impulse, point.offset_b);
+    }
+
+    /// Moves the bodies apart along the normal in proportion to their
+    /// inverse masses.
+    fn correct_positions(
+        &self,
+        bodies: &mut [Body],
+        settings: &PhysicsSettings,
+    ) {
+        let (inv_a, inv_b) =
+            (bodies[self.a].inv_mass(), bodies[self.b].inv_mass());
+        let total = inv_a + inv_b;
+        if total == 0.0 {
+            return;
+        }
+        let push =
+            (self.depth - settings.slop).max(0.0) * settings.correction / total;
+        bodies[self.a].position -= self.normal * (push * inv_a);
+        bodies[self.b].position += self.normal * (push * inv_b);
+    }
+}
+
+/// Advances every [`RigidBody`] by one [`Time::dt`] step using the
+/// [`PhysicsSettings`] resource. Does nothing without it.
+pub fn step_physics(world: &mut World) {
+    let (Some(settings), Some(dt)) = (
+        world
+            .resource::<PhysicsSettings>()
+            .map(|settings| *settings),
+        world
+            .resource::<Time>()
+            .map(|time| time.dt),
+    ) else {
+        return;
+    };
+
+    let mut bodies = gather_bodies(world);
+    // Before gravity, so resting bodies don't look like they're moving
+    // and wake whatever they're lying on.
+    let mut contacts = find_contacts(&mut bodies, &settings);
+    for body in bodies
+        .iter_mut()
+        .filter(|body| body.is_simulated())
+    {
+        body.velocity += settings.gravity * dt;
+    }
+
+    for _ in 0..settings.iterations {
+        for contact in &mut contacts {
+            contact.solve(&mut bodies);
+        }
+    }
+    for contact in &contacts {
+        contact.correct_positions(&mut bodies, &settings);
+    }
+
+    for body in &mut bodies {
+        if !body.is_simulated() {
+            continue;
+        }
+        body.rotation += body.angular_velocity * dt;
+        let still = !body.is_moving(&settings);
+        let rigid = body
+            .rigid
+            .as_mut()
+            .expect("simulated bodies are rigid");
+        if rigid.settle(still, dt, settings.sleep_delay) {
+            body.velocity = Vec2::ZERO;
+            body.angular_velocity = 0.0;
+        }
+    }
+
+    write_back(world, &bodies);
+}
+
+/// Applies `impulse` at the world-space `point` of a rigid body, waking
+/// it up. Does nothing to fixed bodies.
+pub fn apply_impulse(
+    world: &World,
+    entity: Entity,
+    impulse: Vec2,
+    point: Vec2,
+) {
+    let Some(mut rigid) = world.get_mut::<RigidBody>(entity) else {
+        return;
+    };
+    if !rigid.is_dynamic() {
+        return;
+    }
+    rigid.wake();
+
+    let position = world
+        .get::<Position>(entity)
+        .map_or(Vec2::ZERO, |pos| Vec2::new(pos.x, pos.y));
+    if let Some(mut vel) = world.get_mut::<Velocity>(entity) {
+        vel.x += impulse.x / rigid.mass;
+        vel.y += impulse.y / rigid.mass;
+    }
+    let collider = world.get::<Collider>(entity);
+    if let (Some(collider), Some(mut spin)) =
+        (collider, world.get_mut::<AngularVelocity>(entity))
+    {
+        let inertia = collider.0.inertia(rigid.mass);
+        if !rigid.fixed_rotation && inertia > 0.0 {
+            spin.0 += (point - position).cross(impulse) / inertia;
+        }
+    }
+}
+
+fn gather_bodies(world: &World) -> Vec<Body> {
+    let mut bodies = Vec::new();
+    world.query::<(
+        &Position,
+        &Collider,
+        Option<&Velocity>,
+        Option<&Rotation>,
+        Option<&AngularVelocity>,
+        Option<&RigidBody>,
+    )>(|entity, (pos, collider, vel, rotation, spin, rigid)| {
+        let position = Vec2::new(pos.x, pos.y);
+        let rotation = rotation.map_or(0.0, |rotation| rotation.0);
+        let (inv_mass, inv_inertia) = match rigid {
+            Some(rigid) if rigid.is_dynamic() => {
+                let inertia = collider.0.inertia(rigid.mass);
+                let spins = spin.is_some() && !rigid.fixed_rotation;
+                let inv_inertia =
+                    if spins && inertia > 0.0 { 1.0 / inertia } else { 0.0 };
+                (1.0 / rigid.mass, inv_inertia)
+            }
+            _ => (0.0, 0.0),
+        };
+        bodies.push(Body {
+            entity,
+            shape: collider
+                .0
+                .transformed(position, rotation),
+            position,
+            rotation,
+            velocity: vel.map_or(Vec2::ZERO, |vel| Vec2::new(vel.x, vel.y)),
+            angular_velocity: spin.map_or(0.0, |spin| spin.0),
+            inv_mass,
+            inv_inertia,
+            rigid: rigid.copied(),
+        });
+    });
+    bodies
+}
+
+/// Contacts involving at least one awake body, waking sleepers that
+/// something moving runs into.
+fn find_contacts(
+    bodies: &mut [Body],
+    settings: &PhysicsSettings,
+) -> Vec<Contact> {
+    let cell_size = bodies
+        .iter()
+        .map(|body| {
+            let size = body.shape.bounds().half_extents() * 2.0;
+            size.x.max(size.y)
+        })
+        .fold(1.0, f64::max);
+    let mut grid = SpatialHash::new(cell_size);
+    for (index, body) in bodies.iter().enumerate() {
+        grid.insert(index, body.shape.bounds());
+    }
+
+    let mut contacts = Vec::new();
+    for (a, b) in grid.pairs() {
+        if bodies[a].rigid.is_none() && bodies[b].rigid.is_none() {
+            continue;
+        }
+        let Some(manifold) = collide(&bodies[a].shape, &bodies[b].shape) else {
+            continue;
+        };
+        for (sleeper, other) in [(a, b), (b, a)] {
+            if bodies[other].is_moving(settings)
+                && let Some(rigid) = &mut bodies[sleeper].rigid
+            {
+                rigid.wake();
+            }
+        }
+        if bodies[a].is_simulated() || bodies[b].is_simulated() {
+            contacts.push(Contact::new(bodies, (a, b), &manifold, settings));
+        }
+    }
+    contacts
+}
+
+fn write_back(world: &World, bodies: &[Body]) {
+    for body in bodies {
+        let Some(rigid) = body.rigid else {
+            continue;
+        };
+        if let Some(mut stored) = world.get_mut::<RigidBody>(body.entity) {
+            *stored = rigid;
+        }
+        if !rigid.is_dynamic() {
+            continue;
+        }
+        if let Some(mut pos) = world.get_mut::<Position>(body.entity) {
+            *pos = Position { x: body.position.x, y: body.position.y };
+        }
+        if let Some(mut vel) = world.get_mut::<Velocity>(body.entity) {
+            *vel = Velocity { x: body.velocity.x, y: body.velocity.y };
+        }
+        if let Some(mut rotation) = world.get_mut::<Rotation>(body.entity) {
+            rotation.0 = body.rotation;
+        }
+        if let Some(mut spin) = world.get_mut::<AngularVelocity>(body.entity) {
+            spin.0 = body.angular_velocity;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::{Aabb, Circle};
+    use crate::components::PreviousPosition;
+    use crate::systems::integrate_velocity;
+
+    const DT: f64 = 1.0 / 60.0;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.insert_resource(Time { dt: DT });
+        world.insert_resource(PhysicsSettings::default());
+        world
+            .spawn()
+            .with(Position { x: 100.0, y: 210.0 })
+            .with(Collider(Shape::Aabb(Aabb::from_center(
+                Vec2::ZERO,
+                Vec2::new(200.0, 10.0),
+            ))))
+            .with(RigidBody::fixed());
+        world
+    }
+
+    fn spawn_box(world: &mut World, x: f64, y: f64, body: RigidBody) -> Entity {
+        world
+            .spawn()
+            .with(Position { x, y })
+            .with(PreviousPosition::default())
+            .with(Velocity::default())
+            .with(Rotation::default())
+            .with(AngularVelocity::default())
+            .with(Collider(Shape::Aabb(Aabb::from_center(
+                Vec2::ZERO,
+                Vec2::new(10.0, 10.0),
+            ))))
+            .with(body)
+            .id()
+    }
+
+    fn run(world: &mut World, ticks: usize) {
+        for _ in 0..ticks {
+            step_physics(world);
+            integrate_velocity(world);
+        }
+    }
+
+    fn position(world: &World, entity: Entity) -> Position {
+        *world.get::<Position>(entity).unwrap()
+    }
+
+    #[test]
+    fn stacked_boxes_come_to_rest_and_sleep() {
+        let mut world = world();
+        let stack: Vec<_> = (0..3)
+            .map(|i| {
+                let y = 190.0 - 21.0 * i as f64;
+                spawn_box(&mut world, 100.0, y, RigidBody::dynamic(1.0))
+            })
+            .collect();
+
+        run(&mut world, 300);
+
+        let mut floor = 200.0;
+        for (i, &entity) in stack.iter().enumerate() {
+            let pos = position(&world, entity);
+            assert!((pos.x - 100.0).abs() < 0.01, "box {i} slid to {pos:?}");
+            assert!((floor - 10.0 - pos.y).abs() < 1.0, "box {i} at {pos:?}");
+            assert!(
+                world
+                    .get::<RigidBody>(entity)
+                    .unwrap()
+                    .is_asleep()
+            );
+            floor = pos.y - 10.0;
+        }
+    }
+
+    #[test]
+    fn tilted_box_falls_flat() {
+        let mut world = world();
+        let body = spawn_box(&mut world, 100.0, 150.0, RigidBody::dynamic(1.0));
+        world
+            .get_mut::<Rotation>(body)
+            .unwrap()
+            .0 = 0.3;
+
+        run(&mut world, 240);
+
+        let rotation = world.get::<Rotation>(body).unwrap().0;
+        let quarter = std::f64::consts::FRAC_PI_2;
+        let off_flat = (rotation / quarter).round() * quarter - rotation;
+        // Flat to within the slop across its 20px width.
+        assert!(off_flat.abs() < 0.03, "settled at {rotation} radians");
+        assert!((position(&world, body).y - 190.0).abs() < 1.0);
+        assert!(
+            world
+                .get::<RigidBody>(body)
+                .unwrap()
+                .is_asleep()
+        );
+    }
+
+    #[test]
+    fn restitution_controls_the_bounce() {
+        let mut world = world();
+        let dead = spawn_box(&mut world, 40.0, 100.0, RigidBody::dynamic(1.0));
+        let lively = spawn_box(
+            &mut world,
+            160.0,
+            100.0,
+            RigidBody::dynamic(1.0).with_restitution(0.8),
+        );
+
+        // Falling 90px takes about 0.43s; look just after impact.
+        run(&mut world, 30);
+
+        let vel = |entity| world.get::<Velocity>(entity).unwrap().y;
+        assert!(vel(dead).abs() < 20.0, "dead box moving at {}", vel(dead));
+        assert!(vel(lively) < -200.0, "lively box moving at {}", vel(lively));
+    }
+
+    #[test]
+    fn friction_stops_a_sliding_box() {
+        let mut world = world();
+        let grippy =
+            spawn_box(&mut world, 40.0, 190.0, RigidBody::dynamic(1.0));
+        let icy = spawn_box(
+            &mut world,
+            100.0,
+            190.0,
+            RigidBody::dynamic(1.0).with_friction(0.0),
+        );
+        for entity in [grippy, icy] {
+            world
+                .get_mut::<Velocity>(entity)
+                .unwrap()
+                .x = 100.0;
+        }
+
+        run(&mut world, 60);
+
+        assert!(position(&world, grippy).x < 60.0);
+        assert!((position(&world, icy).x - 200.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn off_center_impulse_spins_and_wakes() {
+        let mut world = world();
+        world.insert_resource(PhysicsSettings {
+            gravity: Vec2::ZERO,
+            ..PhysicsSettings::default()
+        });
+        let body = spawn_box(&mut world, 100.0, 100.0, RigidBody::dynamic(2.0));
+        run(&mut world, 60);
+        assert!(
+            world
+                .get::<RigidBody>(body)
+                .unwrap()
+                .is_asleep()
+        );
+
+        apply_impulse(
+            &world,
+            body,
+            Vec2::new(0.0, 20.0),
+            Vec2::new(110.0, 100.0),
+        );
+
+        assert!(
+            !world
+                .get::<RigidBody>(body)
+                .unwrap()
+                .is_asleep()
+        );
+        assert_eq!(world.get::<Velocity>(body).unwrap().y, 10.0);
+        // Pushing the right edge down turns the box clockwise.
+        assert!(
+            world
+                .get::<AngularVelocity>(body)
+                .unwrap()
+                .0
+                > 0.0
+        );
+    }
+
+    #[test]
+    fn heavy_ball_pushes_a_light_one_further() {
+        let mut world = world();
+        world.insert_resource(PhysicsSettings {
+            gravity: Vec2::ZERO,
+            ..PhysicsSettings::default()
+        });
+        let ball = |world: &mut World, x: f64, mass: f64, vx: f64| {
+            world
+                .spawn()
+                .with(Position { x, y: 50.0 })
+                .with(Velocity { x: vx, y: 0.0 })
+                .with(Collider(Shape::Circle(Circle::new(Vec2::ZERO, 5.0))))
+                .with(RigidBody::dynamic(mass).with_restitution(1.0))
+                .id()
+        };
+        let heavy = ball(&mut world, 50.0, 3.0, 100.0);
+        let light = ball(&mut world, 80.0, 1.0, 0.0);
+
+        run(&mut world, 30);
+
+        // Elastic 3:1 collision: 100 -> 50 and 0 -> 150.
+        let vel = |entity| world.get::<Velocity>(entity).unwrap().x;
+        assert!((vel(heavy) - 50.0).abs() < 1e-6, "heavy at {}", vel(heavy));
+        assert!((vel(light) - 150.0).abs() < 1e-6, "light at {}", vel(light));
+    }
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::framebuffer::Framebuffer;
+
+/// Encodes a framebuffer as a binary (`P6`) PPM image.
+pub fn encode(framebuffer: &Framebuffer) -> Vec<u8> {
+    let header =
+        format!("P6\n{} {}\n255\n", framebuffer.width(), framebuffer.height());
+
+    let mut bytes = header.into_bytes();
+    bytes.reserve(framebuffer.pixels().len() * 3);
+    for &pixel in framebuffer.pixels() {
+        bytes.push((pixel >> 16) as u8);
+        bytes.push((pixel >> 8) as u8);
+        bytes.push(pixel as u8);
+    }
+
+    bytes
+}
+
+/// Decodes a binary (`P6`) PPM image with a max value of 255.
+pub fn decode(bytes: &[u8]) -> io::Result<Framebuffer> {
+    let mut cursor = 0;
+    let mut fields = [0usize; 3];
+
+    if bytes.get(..2) != Some(b"P6") {
+        return Err(invalid("missing P6 magic number"));
+    }
+    cursor += 2;
+
+    for field in &mut fields {
+        *field = read_header_number(bytes, &mut cursor)?;
+    }
+    let [width, height, max_value] = fields;
+
+    if max_value != 255 {
+        return Err(invalid("only 8-bit PPM images are supported"));
+    }
+
+    // Exactly one whitespace byte separates the header from the raster.
+    cursor += 1;
+
+    let end = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(3))
+        .and_then(|len| len.checked_add(cursor))
+        .ok_or_else(|| invalid("image is too large"))?;
+    let data = bytes
+        .get(cursor..end)
+        .ok_or_else(|| invalid("pixel data is truncated"))?;
+
+    let mut framebuffer = Framebuffer::new(width, height);
+    for (pixel, rgb) in framebuffer
+        .pixels_mut()
+        .iter_mut()
+        .zip(data.chunks_exact(3))
+    {
+        *pixel = (rgb[0] as u32) << 16 | (rgb[1] as u32) << 8 | rgb[2] as u32;
+    }
+
+    Ok(framebuffer)
+}
+
+pub fn save(
+    path: impl AsRef<Path>,
+    framebuffer: &Framebuffer,
+) -> io::Result<()> {
+    fs::write(path, encode(framebuffer))
+}
+
+pub fn load(path: impl AsRef<Path>) -> io::Result<Framebuffer> {
+    decode(&fs::read(path)?)
+}
+
+fn read_header_number(bytes: &[u8], cursor: &mut usize) -> io::Result<usize> {
+    loop {
+        match bytes.get(*cursor) {
+            Some(b'#') => {
+                while !matches!(bytes.get(*cursor), Some(b'\n') | None) {
+                    *cursor += 1;
+                }
+            }
+            Some(byte) if byte.is_ascii_whitespace() => *cursor += 1,
+            _ => break,
+        }
+    }
+
+    let start = *cursor;
+    while bytes
+        .get(*cursor)
+        .is_some_and(u8::is_ascii_digit)
+    {
+        *cursor += 1;
+    }
+
+    std::str::from_utf8(&bytes[start..*cursor])
+        .ok()
+        .and_then(|digits| digits.parse().ok())
+        .ok_or_else(|| invalid("malformed header"))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("PPM: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_pixels() {
+        let mut framebuffer = Framebuffer::new(3, 2);
+        framebuffer.set_pixel(0, 0, 0x00FF0000);
+        framebuffer.set_pixel(2, 1, 0x000080FF);
+
+        let decoded = decode(&encode(&framebuffer)).unwrap();
+
+        assert_eq!(decoded, framebuffer);
+    }
+
+    #[test]
+    fn skips_header_comments() {
+        let bytes = b"P6\n# comment\n1 1\n255\n\x01\x02\x03";
+
+        let decoded = decode(bytes).unwrap();
+
+        assert_eq!(decoded.pixel(0, 0), Some(0x00010203));
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        assert!(decode(b"P6 2 2 255 \x00\x00\x00").is_err());
+    }
+
+    #[test]
+    fn rejects_overflowing_dimensions() {
+        assert!(decode(b"P6 4294967296 4294967296 255\n").is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use std::num::NonZeroU32;
+use std::sync::Arc;
+
+use softbuffer::{Context, Surface};
+use winit::window::Window;
+
+use crate::error::Error;
+use crate::framebuffer::Framebuffer;
+
+/// Copies a finished [`Framebuffer`] to a window through softbuffer.
+pub struct SurfacePresenter {
+    window: Arc<Window>,
+    context: Context<Arc<Window>>,
+    surface: Surface<Arc<Window>, Arc<Window>>,
+}
+
+impl SurfacePresenter {
+    pub fn new(window: Window) -> Result<Self, Error> {
+        let window = Arc::new(window);
+        let context = Context::new(window.clone())?;
+        let surface = Surface::new(&context, window.clone())?;
+
+        Ok(Self { window, context, surface })
+    }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    /// Window size in physical pixels, never smaller than 1x1.
+    pub fn size(&self) -> (usize, usize) {
+        let size = self.window.inner_size();
+        (size.width.max(1) as usize, size.height.max(1) as usize)
+    }
+
+    /// Shows `framebuffer` in the window. A surface lost to a resize or a
+    /// display change is recreated and the frame retried once.
+    pub fn present(&mut self, framebuffer: &Framebuffer) -> Result<(), Error> {
+        let (Some(w), Some(h)) = (
+            NonZeroU32::new(framebuffer.width() as u32),
+            NonZeroU32::new(framebuffer.height() as u32),
+        ) else {
+            return Ok(());
+        };
+
+        if self
+            .try_present(framebuffer, w, h)
+            .is_ok()
+        {
+            return Ok(());
+        }
+        self.surface = Surface::new(&self.context, self.window.clone())?;
+        self.try_present(framebuffer, w, h)
+    }
+
+    fn try_present(
+        &mut self,
+        framebuffer: &Framebuffer,
+        width: NonZeroU32,
+        height: NonZeroU32,
+    ) -> Result<(), Error> {
+        self.surface.resize(width, height)?;
+        let mut buffer = self.surface.buffer_mut()?;
+        buffer.copy_from_slice(framebuffer.pixels());
+        buffer.present()?;
+        Ok(())
+    }
+}
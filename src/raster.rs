@@ -0,0 +1,533 @@
+//! 2D primitive rasterization on [`Framebuffer`].
+//!
+//! Integer primitives take pixel coordinates; polygons take sub-pixel
+//! vertices and fill every pixel whose center lies inside (even-odd
+//! rule). Everything is clipped to the buffer bounds.
+
+use crate::color::Color;
+use crate::framebuffer::Framebuffer;
+
+/// Keeps clipped sub-pixel endpoints strictly inside the last column/row.
+const CLIP_EPSILON: f64 = 1e-9;
+
+impl Framebuffer {
+    /// Blends a pixel at signed coordinates, skipping anything off-buffer.
+    pub fn put_pixel(&mut self, x: i32, y: i32, color: Color) {
+        if x < 0 || y < 0 {
+            return;
+        }
+        self.blend_pixel(x as usize, y as usize, color);
+    }
+
+    /// Fills the pixels `x0..=x1` on row `y`.
+    pub fn draw_hline(&mut self, x0: i32, x1: i32, y: i32, color: Color) {
+        let (x0, x1) = (x0.min(x1), x0.max(x1));
+        if y < 0 || y as usize >= self.height() || x1 < 0 || self.width() == 0 {
+            return;
+        }
+
+        let start = x0.max(0) as usize;
+        let end = (x1 as usize).min(self.width() - 1);
+        if start > end {
+            return;
+        }
+
+        let row = y as usize * self.width();
+        self.blend_span(row + start, end - start + 1, color);
+    }
+
+    /// Bresenham line between two pixel coordinates, inclusive.
+    pub fn draw_line(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        color: Color,
+    ) {
+        if self.width() == 0 || self.height() == 0 {
+            return;
+        }
+        let (max_x, max_y) = (self.width() - 1, self.height() - 1);
+        let Some((x0, y0, x1, y1)) = clip_line(
+            (x0 as f64, y0 as f64, x1 as f64, y1 as f64),
+            max_x as f64,
+            max_y as f64,
+        ) else {
+            return;
+        };
+        let (mut x, mut y) = (x0.round() as i32, y0.round() as i32);
+        let (x1, y1) = (x1.round() as i32, y1.round() as i32);
+
+        let dx = (x1 - x).abs();
+        let dy = -(y1 - y).abs();
+        let sx = if x < x1 { 1 } else { -1 };
+        let sy = if y < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.put_pixel(x, y, color);
+            if x == x1 && y == y1 {
+                break;
+            }
+
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// DDA line between sub-pixel endpoints.
+    pub fn draw_line_f(
+        &mut self,
+        x0: f64,
+        y0: f64,
+        x1: f64,
+        y1: f64,
+        color: Color,
+    ) {
+        let max_x = self.width() as f64 - CLIP_EPSILON;
+        let max_y = self.height() as f64 - CLIP_EPSILON;
+        let Some((x0, y0, x1, y1)) = clip_line((x0, y0, x1, y1), max_x, max_y)
+        else {
+            return;
+        };
+
+        let dx = x1 - x0;
+        let dy = y1 - y0;
+        let steps = dx.abs().max(dy.abs()).ceil().max(1.0);
+        let (step_x, step_y) = (dx / steps, dy / steps);
+
+        for i in 0..=steps as usize {
+            let t = i as f64;
+            let x = (x0 + step_x * t).floor() as i32;
+            let y = (y0 + step_y * t).floor() as i32;
+            self.put_pixel(x, y, color);
+        }
+    }
+
+    /// Line of the given width, rasterized as a filled quad.
+    pub fn draw_thick_line(
+        &mut self,
+        x0: f64,
+        y0: f64,
+        x1: f64,
+        y1: f64,
+        thickness: f64,
+        color: Color,
+    ) {
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let length = dx.hypot(dy);
+        if thickness <= 1.0 || length == 0.0 {
+            self.draw_line_f(x0, y0, x1, y1, color);
+            return;
+        }
+
+        let half = thickness / 2.0;
+        let (nx, ny) = (-dy / length * half, dx / length * half);
+        self.fill_polygon(
+            &[
+                (x0 + nx, y0 + ny),
+                (x1 + nx, y1 + ny),
+                (x1 - nx, y1 - ny),
+                (x0 - nx, y0 - ny),
+            ],
+            color,
+        );
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: Color) {
+        if w <= 0 || h <= 0 {
+            return;
+        }
+
+        let y_start = y.max(0);
+        let y_end = y
+            .saturating_add(h)
+            .min(self.height() as i32);
+        for row in y_start..y_end {
+            self.draw_hline(x, x.saturating_add(w - 1), row, color);
+        }
+    }
+
+    /// One-pixel outline of the rectangle filled by [`Self::fill_rect`].
+    pub fn draw_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: Color) {
+        if w <= 0 || h <= 0 {
+            return;
+        }
+
+        // Saturated edges are off-screen either way.
+        let right = x.saturating_add(w - 1);
+        let bottom = y.saturating_add(h - 1);
+        self.draw_hline(x, right, y, color);
+        self.draw_hline(x, right, bottom, color);
+        let rows = y.saturating_add(1).max(0)..bottom.min(self.height() as i32);
+        for row in rows {
+            self.put_pixel(x, row, color);
+            self.put_pixel(right, row, color);
+        }
+    }
+
+    pub fn fill_circle(&mut self, cx: i32, cy: i32, radius: i32, color: Color) {
+        self.fill_ellipse(cx, cy, radius, radius, color);
+    }
+
+    /// Midpoint circle outline.
+    pub fn draw_circle(&mut self, cx: i32, cy: i32, radius: i32, color: Color) {
+        if radius < 0 {
+            return;
+        }
+
+        let (mut x, mut y) = (radius, 0);
+        let mut err = 1 - radius;
+        while x >= y {
+            for (px, py) in [
+                (x, y),
+                (y, x),
+                (-y, x),
+                (-x, y),
+                (-x, -y),
+                (-y, -x),
+                (y, -x),
+                (x, -y),
+            ] {
+                self.put_pixel(cx + px, cy + py, color);
+            }
+
+            y += 1;
+            if err < 0 {
+                err += 2 * y + 1;
+            } else {
+                x -= 1;
+                err += 2 * (y - x) + 1;
+            }
+        }
+    }
+
+    pub fn fill_ellipse(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        rx: i32,
+        ry: i32,
+        color: Color,
+    ) {
+        if rx < 0 || ry < 0 {
+            return;
+        }
+
+        let (rx_f, ry_f) = (rx as f64 + 0.5, ry as f64 + 0.5);
+        for dy in -ry..=ry {
+            let t = dy as f64 / ry_f;
+            let half = (rx_f * (1.0 - t * t).sqrt()).floor() as i32;
+            self.draw_hline(cx - half, cx + half, cy + dy, color);
+        }
+    }
+
+    /// Midpoint ellipse outline.
+    pub fn draw_ellipse(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        rx: i32,
+        ry: i32,
+        color: Color,
+    ) {
+        if rx < 0 || ry < 0 {
+            return;
+        }
+
+        let plot = |fb: &mut Self, x: i32, y: i32| {
+            fb.put_pixel(cx + x, cy + y, color);
+            fb.put_pixel(cx - x, cy + y, color);
+            fb.put_pixel(cx + x, cy - y, color);
+            fb.put_pixel(cx - x, cy - y, color);
+        };
+
+        let (rx2, ry2) = (rx as i64 * rx as i64, ry as i64 * ry as i64);
+        let (mut x, mut y) = (0i64, ry as i64);
+        let (mut px, mut py) = (0i64, 2 * rx2 * y);
+
+        // Region 1: slope shallower than -1.
+        let mut p = ry2 - rx2 * ry as i64 + rx2 / 4;
+        while px < py {
+            plot(self, x as i32, y as i32);
+            x += 1;
+            px += 2 * ry2;
+            if p < 0 {
+                p += ry2 + px;
+            } else {
+                y -= 1;
+                py -= 2 * rx2;
+                p += ry2 + px - py;
+            }
+        }
+
+        // Region 2: slope steeper than -1.
+        let (xf, yf) = (x as f64 + 0.5, (y - 1) as f64);
+        let mut p = (ry2 as f64 * xf * xf + rx2 as f64 * yf * yf
+            - (rx2 * ry2) as f64) as i64;
+        while y >= 0 {
+            plot(self, x as i32, y as i32);
+            y -= 1;
+            py -= 2 * rx2;
+            if p > 0 {
+                p += rx2 - py;
+            } else {
+                x += 1;
+                px += 2 * ry2;
+                p += rx2 - py + px;
+            }
+        }
+    }
+
+    pub fn fill_triangle(
+        &mut self,
+        a: (f64, f64),
+        b: (f64, f64),
+        c: (f64, f64),
+        color: Color,
+    ) {
+        self.fill_polygon(&[a, b, c], color);
+    }
+
+    pub fn draw_triangle(
+        &mut self,
+        a: (f64, f64),
+        b: (f64, f64),
+        c: (f64, f64),
+        color: Color,
+    ) {
+        self.draw_polygon(&[a, b, c], color);
+    }
+
+    /// Scanline fill of an arbitrary (convex, concave or
+    /// self-intersecting) polygon using the even-odd rule.
+    pub fn fill_polygon(&mut self, points: &[(f64, f64)], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let (min_y, max_y) = points
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                (lo.min(p.1), hi.max(p.1))
+            });
+        let row_start = ((min_y - 0.5).ceil() as i32).max(0);
+        let row_end = ((max_y - 0.5).ceil() as i32).min(self.height() as i32);
+
+        let mut crossings = Vec::new();
+        for row in row_start..row_end {
+            let sample_y = row as f64 + 0.5;
+
+            crossings.clear();
+            for (i, &(x0, y0)) in points.iter().enumerate() {
+                let (x1, y1) = points[(i + 1) % points.len()];
+                if (y0 <= sample_y) != (y1 <= sample_y) {
+                    let t = (sample_y - y0) / (y1 - y0);
+                    crossings.push(x0 + t * (x1 - x0));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+
+            for span in crossings.chunks_exact(2) {
+                let start = (span[0] - 0.5).ceil() as i32;
+                let end = (span[1] - 0.5).ceil() as i32 - 1;
+                if start <= end {
+                    self.draw_hline(start, end, row, color);
+                }
+            }
+        }
+    }
+
+    /// Closed outline through `points`.
+    pub fn draw_polygon(&mut self, points: &[(f64, f64)], color: Color) {
+        for (i, &(x0, y0)) in points.iter().enumerate() {
+            let (x1, y1) = points[(i + 1) % points.len()];
+            self.draw_line_f(x0, y0, x1, y1, color);
+        }
+    }
+}
+
+/// Liang-Barsky clip of a segment against `[0, max_x] x [0, max_y]`.
+fn clip_line(
+    (x0, y0, x1, y1): (f64, f64, f64, f64),
+    max_x: f64,
+    max_y: f64,
+) -> Option<(f64, f64, f64, f64)> {
+    let (dx, dy) = (x1 - x0, y1 - y0);
+
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for (p, q) in [(-dx, x0), (dx, max_x - x0), (-dy, y0), (dy, max_y - y0)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let r = q / p;
+            if p < 0.0 {
+                t0 = t0.max(r);
+            } else {
+                t1 = t1.min(r);
+            }
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    Some((x0 + t0 * dx, y0 + t0 * dy, x0 + t1 * dx, y0 + t1 * dy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::BlendMode;
+
+    const ON: Color = Color::WHITE;
+    const ON_PIXEL: u32 = 0x00FFFFFF;
+
+    fn count(fb: &Framebuffer) -> usize {
+        fb.pixels()
+            .iter()
+            .filter(|&&p| p == ON_PIXEL)
+            .count()
+    }
+
+    #[test]
+    fn bresenham_covers_both_endpoints() {
+        let mut fb = Framebuffer::new(10, 10);
+        fb.draw_line(1, 1, 8, 4, ON);
+
+        assert_eq!(fb.pixel(1, 1), Some(ON_PIXEL));
+        assert_eq!(fb.pixel(8, 4), Some(ON_PIXEL));
+        assert_eq!(count(&fb), 8);
+    }
+
+    #[test]
+    fn lines_are_clipped_to_bounds() {
+        let mut fb = Framebuffer::new(10, 10);
+        fb.draw_line(-100, 5, 100, 5, ON);
+        fb.draw_line_f(5.0, -50.0, 5.0, 50.0, ON);
+
+        assert_eq!(count(&fb), 19);
+    }
+
+    #[test]
+    fn empty_framebuffers_draw_nothing() {
+        for (width, height) in [(0, 0), (0, 4), (4, 0)] {
+            let mut fb = Framebuffer::new(width, height);
+            fb.draw_line(0, 0, 3, 3, ON);
+            fb.draw_line_f(0.0, 0.0, 3.0, 3.0, ON);
+            fb.draw_hline(0, 3, 0, ON);
+            fb.fill_rect(0, 0, 4, 4, ON);
+            fb.fill_circle(2, 2, 2, ON);
+        }
+    }
+
+    #[test]
+    fn fill_rect_clips_negative_origin() {
+        let mut fb = Framebuffer::new(4, 4);
+        fb.fill_rect(-2, -2, 4, 4, ON);
+
+        assert_eq!(count(&fb), 4);
+        assert_eq!(fb.pixel(1, 1), Some(ON_PIXEL));
+        assert_eq!(fb.pixel(2, 2), Some(0));
+    }
+
+    #[test]
+    fn huge_rects_clip_without_overflowing() {
+        let mut fb = Framebuffer::new(4, 4);
+        fb.fill_rect(i32::MAX - 1, i32::MAX - 1, 10, 10, ON);
+        fb.draw_rect(i32::MAX - 1, i32::MAX - 1, 10, 10, ON);
+        assert_eq!(count(&fb), 0);
+
+        fb.draw_rect(1, 1, i32::MAX, i32::MAX, ON);
+        assert_eq!(count(&fb), 5);
+
+        fb.fill_rect(-1, -1, i32::MAX, i32::MAX, ON);
+        assert_eq!(count(&fb), 16);
+    }
+
+    #[test]
+    fn draw_rect_outlines_perimeter() {
+        let mut fb = Framebuffer::new(6, 6);
+        fb.draw_rect(1, 1, 4, 3, ON);
+
+        assert_eq!(count(&fb), 10);
+        assert_eq!(fb.pixel(2, 2), Some(0));
+    }
+
+    #[test]
+    fn polygon_fill_matches_rect_area() {
+        let mut fb = Framebuffer::new(10, 10);
+        fb.fill_polygon(&[(2.0, 2.0), (6.0, 2.0), (6.0, 5.0), (2.0, 5.0)], ON);
+
+        assert_eq!(count(&fb), 12);
+    }
+
+    #[test]
+    fn concave_polygon_leaves_notch_empty() {
+        let mut fb = Framebuffer::new(10, 10);
+        let u_shape = [
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 6.0),
+            (6.0, 6.0),
+            (6.0, 0.0),
+            (9.0, 0.0),
+            (9.0, 9.0),
+            (0.0, 9.0),
+        ];
+        fb.fill_polygon(&u_shape, ON);
+
+        assert_eq!(fb.pixel(4, 2), Some(0));
+        assert_eq!(fb.pixel(1, 2), Some(ON_PIXEL));
+        assert_eq!(fb.pixel(4, 7), Some(ON_PIXEL));
+    }
+
+    #[test]
+    fn circle_fill_is_symmetric() {
+        let mut fb = Framebuffer::new(21, 21);
+        fb.fill_circle(10, 10, 5, ON);
+
+        for (x, y) in [(5, 10), (15, 10), (10, 5), (10, 15)] {
+            assert_eq!(fb.pixel(x, y), Some(ON_PIXEL), "({x}, {y})");
+        }
+        assert_eq!(fb.pixel(4, 10), Some(0));
+        assert_eq!(fb.pixel(14, 14), Some(0));
+    }
+
+    #[test]
+    fn outlines_touch_their_extremes() {
+        let mut fb = Framebuffer::new(30, 30);
+        fb.draw_circle(10, 10, 6, ON);
+        fb.draw_ellipse(15, 15, 12, 4, ON);
+
+        for (x, y) in [(4, 10), (16, 10), (10, 4), (3, 15), (27, 15), (15, 19)]
+        {
+            assert_eq!(fb.pixel(x, y), Some(ON_PIXEL), "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn draw_calls_respect_blend_mode() {
+        let mut fb = Framebuffer::new(4, 1);
+        fb.fill(Color::rgb(0x80, 0x80, 0x80));
+        fb.set_blend_mode(BlendMode::Additive);
+        fb.draw_hline(0, 1, 0, Color::rgb(0x10, 0x20, 0x30));
+        fb.set_blend_mode(BlendMode::SourceOver);
+        fb.put_pixel(3, 0, Color::BLACK.with_alpha(0));
+
+        assert_eq!(fb.pixel(0, 0), Some(0x0090A0B0));
+        assert_eq!(fb.pixel(2, 0), Some(0x00808080));
+        assert_eq!(fb.pixel(3, 0), Some(0x00808080));
+    }
+}
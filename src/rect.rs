@@ -0,0 +1,57 @@
+/// Integer rectangle in pixel coordinates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+impl Rect {
+    pub const fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    pub const fn right(&self) -> i32 {
+        self.x + self.w
+    }
+
+    pub const fn bottom(&self) -> i32 {
+        self.y + self.h
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.w <= 0 || self.h <= 0
+    }
+
+    pub const fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// Overlapping area of two rectangles, if any.
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        let rect = Rect::new(x, y, right - x, bottom - y);
+        (!rect.is_empty()).then_some(rect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intersect_overlapping_and_disjoint() {
+        let a = Rect::new(0, 0, 10, 10);
+
+        assert_eq!(
+            a.intersect(&Rect::new(5, -5, 10, 10)),
+            Some(Rect::new(5, 0, 5, 5))
+        );
+        assert_eq!(a.intersect(&Rect::new(10, 0, 5, 5)), None);
+    }
+}
@@ -0,0 +1,652 @@
+//! Entities and their components saved as human-editable RON or JSON.
+//!
+//! A RON scene looks like:
+//!
+//! ```ron
+//! (
+//!     version: 1,
+//!     entities: [
+//!         (
+//!             name: "player",
+//!             position: (x: 16, y: 12),
+//!             velocity: (x: 100, y: 100),
+//!             bounds: (w: 16, h: 12),
+//!             player: (speed: 300),
+//!         ),
+//!         (
+//!             parent: "player",
+//!             transform: (translation: (x: 0, y: -10)),
+//!             label: (text: "P1"),
+//!         ),
+//!     ],
+//! )
+//! ```
+//!
+//! Every component is optional. Sprite paths are relative to the scene
+//! file, and parents are referred to by name.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ron::extensions::Extensions;
+use serde::{Deserialize, Serialize};
+
+use crate::assets::{Assets, LoadState};
+use crate::collision::Shape;
+use crate::color::Color;
+use crate::components::{
+    AngularVelocity, Bounds, Collider, Drawable, Fill, Label, LocalTransform,
+    Name, Parent, PlayerControlled, Position, PreviousPosition, RigidBody,
+    Rotation, Sprite, Velocity,
+};
+use crate::ecs::{Entity, World};
+use crate::image::{Image, ImageError};
+use crate::physics::PhysicsSettings;
+use crate::transform::Transform2D;
+
+/// Version written by [`Scene::save`]. Files with a newer version are
+/// rejected rather than half-understood.
+pub const SCENE_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SceneFormat {
+    Ron,
+    Json,
+}
+
+impl SceneFormat {
+    /// Format named by `path`'s extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "ron" => Some(Self::Ron),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SceneFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ron => "RON",
+            Self::Json => "JSON",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(io::Error),
+    UnknownFormat(PathBuf),
+    Parse {
+        format: SceneFormat,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Serialize(String),
+    UnsupportedVersion {
+        found: u32,
+    },
+    DuplicateName(String),
+    UnknownParent {
+        entity: usize,
+        parent: String,
+    },
+    Sprite {
+        path: PathBuf,
+        error: ImageError,
+    },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to access scene: {err}"),
+            Self::UnknownFormat(path) => write!(
+                f,
+                "{} is not a scene; expected a .ron or .json file",
+                path.display()
+            ),
+            Self::Parse { format, line, column, message } => {
+                write!(f, "{format} scene {line}:{column}: {message}")
+            }
+            Self::Serialize(message) => {
+                write!(f, "failed to serialize scene: {message}")
+            }
+            Self::UnsupportedVersion { found } => write!(
+                f,
+                "scene version {found} is newer than the supported \
+                 version {SCENE_VERSION}"
+            ),
+            Self::DuplicateName(name) => {
+                write!(f, "more than one entity is named {name:?}")
+            }
+            Self::UnknownParent { entity, parent } => write!(
+                f,
+                "entity {entity} has parent {parent:?}, but no entity has \
+                 that name"
+            ),
+            Self::Sprite { path, error } => {
+                write!(f, "sprite {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Sprite { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SceneError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for SceneError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Parse {
+            format: SceneFormat::Ron,
+            line: err.span.start.line,
+            column: err.span.start.col,
+            message: err.code.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for SceneError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Parse {
+            format: SceneFormat::Json,
+            line: err.line(),
+            column: err.column(),
+            message: err.to_string(),
+        }
+    }
+}
+
+/// A level's entities plus the physics tuning it was designed for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physics: Option<PhysicsSettings>,
+    #[serde(default)]
+    pub entities: Vec<EntityData>,
+}
+
+/// One entity's components, each optional.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EntityData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Name of the entity [`transform`](Self::transform) is relative to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    /// Clockwise radians.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<Velocity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub angular_velocity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform2D>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<Bounds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fill: Option<Color>,
+    /// Image path relative to the scene file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sprite: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collider: Option<Shape>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drawable: Option<Shape>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rigid_body: Option<RigidBody>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player: Option<PlayerControlled>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<Label>,
+}
+
+/// Just the version, read before the rest so newer files fail with a
+/// version error instead of a confusing field error.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+fn ron_options() -> ron::Options {
+    ron::Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME)
+        .with_default_extension(Extensions::UNWRAP_VARIANT_NEWTYPES)
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self {
+            version: SCENE_VERSION,
+            physics: None,
+            entities: Vec::new(),
+        }
+    }
+}
+
+impl Scene {
+    /// Loads a `.ron` or `.json` scene.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        let format = SceneFormat::from_path(path)
+            .ok_or_else(|| SceneError::UnknownFormat(path.to_owned()))?;
+        Self::parse(&fs::read_to_string(path)?, format)
+    }
+
+    pub fn parse(
+        source: &str,
+        format: SceneFormat,
+    ) -> Result<Self, SceneError> {
+        let header: Header = match format {
+            SceneFormat::Ron => ron_options().from_str(source)?,
+            SceneFormat::Json => serde_json::from_str(source)?,
+        };
+        if header.version > SCENE_VERSION {
+            return Err(SceneError::UnsupportedVersion {
+                found: header.version,
+            });
+        }
+        Ok(match format {
+            SceneFormat::Ron => ron_options().from_str(source)?,
+            SceneFormat::Json => serde_json::from_str(source)?,
+        })
+    }
+
+    /// Saves in the format named by `path`'s extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SceneError> {
+        let path = path.as_ref();
+        let format = SceneFormat::from_path(path)
+            .ok_or_else(|| SceneError::UnknownFormat(path.to_owned()))?;
+        fs::write(path, self.to_string(format)?)?;
+        Ok(())
+    }
+
+    pub fn to_string(&self, format: SceneFormat) -> Result<String, SceneError> {
+        match format {
+            SceneFormat::Ron => ron::ser::to_string_pretty(
+                self,
+                ron::ser::PrettyConfig::default().extensions(
+                    Extensions::IMPLICIT_SOME
+                        | Extensions::UNWRAP_VARIANT_NEWTYPES,
+                ),
+            )
+            .map_err(|err| SceneError::Serialize(err.to_string())),
+            SceneFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|err| SceneError::Serialize(err.to_string())),
+        }
+    }
+
+    /// Describes every entity in `world`. Unnamed parents are given
+    /// names so their children can refer to them.
+    pub fn capture(world: &World) -> Self {
+        let entities: Vec<Entity> = world.entities().collect();
+        let mut names: HashMap<Entity, String> = entities
+            .iter()
+            .filter_map(|&entity| {
+                Some((entity, world.get::<Name>(entity)?.0.clone()))
+            })
+            .collect();
+        let mut taken: HashSet<String> = names.values().cloned().collect();
+        for &entity in &entities {
+            let Some(parent) = world.get::<Parent>(entity).map(|p| p.0) else {
+                continue;
+            };
+            if names.contains_key(&parent) || !world.is_alive(parent) {
+                continue;
+            }
+            let mut name = format!("entity{}", parent.index());
+            while taken.contains(&name) {
+                name.push('_');
+            }
+            taken.insert(name.clone());
+            names.insert(parent, name);
+        }
+
+        let entities = entities
+            .iter()
+            .map(|&entity| EntityData {
+                name: names.get(&entity).cloned(),
+                parent: world
+                    .get::<Parent>(entity)
+                    .and_then(|parent| names.get(&parent.0).cloned()),
+                position: world
+                    .get::<Position>(entity)
+                    .as_deref()
+                    .copied(),
+                rotation: world
+                    .get::<Rotation>(entity)
+                    .map(|r| r.0),
+                velocity: world
+                    .get::<Velocity>(entity)
+                    .as_deref()
+                    .copied(),
+                angular_velocity: world
+                    .get::<AngularVelocity>(entity)
+                    .map(|v| v.0),
+                transform: world
+                    .get::<LocalTransform>(entity)
+                    .map(|t| t.0),
+                bounds: world
+                    .get::<Bounds>(entity)
+                    .as_deref()
+                    .copied(),
+                fill: world
+                    .get::<Fill>(entity)
+                    .map(|fill| fill.0),
+                sprite: world
+                    .get::<Sprite>(entity)
+                    .map(|sprite| sprite.path.clone()),
+                collider: world
+                    .get::<Collider>(entity)
+                    .map(|c| c.0),
+                drawable: world
+                    .get::<Drawable>(entity)
+                    .map(|d| d.0),
+                rigid_body: world
+                    .get::<RigidBody>(entity)
+                    .as_deref()
+                    .copied(),
+                player: world
+                    .get::<PlayerControlled>(entity)
+                    .as_deref()
+                    .copied(),
+                label: world
+                    .get::<Label>(entity)
+                    .as_deref()
+                    .cloned(),
+            })
+            .collect();
+
+        Self {
+            version: SCENE_VERSION,
+            physics: world
+                .resource::<PhysicsSettings>()
+                .as_deref()
+                .copied(),
+            entities,
+        }
+    }
+
+    /// Adds the scene's entities to `world`, loading sprites relative to
+    /// `base_dir` into the world's [`Assets`], and returns them in file
+    /// order. Nothing is spawned if the scene is invalid.
+    pub fn spawn(
+        &self,
+        world: &mut World,
+        base_dir: impl AsRef<Path>,
+    ) -> Result<Vec<Entity>, SceneError> {
+        let mut by_name = HashMap::new();
+        for (index, data) in self.entities.iter().enumerate() {
+            if let Some(name) = &data.name
+                && by_name
+                    .insert(name.as_str(), index)
+                    .is_some()
+            {
+                return Err(SceneError::DuplicateName(name.clone()));
+            }
+        }
+        let parents = self
+            .entities
+            .iter()
+            .enumerate()
+            .map(|(index, data)| {
+                data.parent
+                    .as_deref()
+                    .map(|parent| {
+                        by_name
+                            .get(parent)
+                            .copied()
+                            .ok_or_else(|| SceneError::UnknownParent {
+                                entity: index,
+                                parent: parent.to_owned(),
+                            })
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if world.resource::<Assets>().is_none() {
+            world.insert_resource(Assets::new());
+        }
+        // Sprites loaded before a later one fails are freed with their
+        // handles.
+        let sprites = {
+            let mut assets = world
+                .resource_mut::<Assets>()
+                .expect("assets were inserted above");
+            self.entities
+                .iter()
+                .map(|data| {
+                    data.sprite
+                        .as_ref()
+                        .map(|path| {
+                            load_sprite(&mut assets, base_dir.as_ref(), path)
+                        })
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let spawned: Vec<Entity> = self
+            .entities
+            .iter()
+            .zip(sprites)
+            .map(|(data, sprite)| spawn_entity(world, data, sprite))
+            .collect();
+        for (&entity, parent) in spawned.iter().zip(parents) {
+            if let Some(parent) = parent {
+                world.insert(entity, Parent(spawned[parent]));
+            }
+        }
+        if let Some(physics) = self.physics {
+            world.insert_resource(physics);
+        }
+        Ok(spawned)
+    }
+}
+
+/// Shares the image with other sprites of the same file when it's
+/// already loaded.
+fn load_sprite(
+    assets: &mut Assets,
+    base_dir: &Path,
+    path: &Path,
+) -> Result<Sprite, SceneError> {
+    let full_path = base_dir.join(path);
+    let image = match assets.handle::<Image>(&full_path) {
+        Some(image) if assets.load_state(&image) == LoadState::Loaded => image,
+        _ => {
+            let image = Image::load(&full_path).map_err(|error| {
+                SceneError::Sprite { path: path.to_owned(), error }
+            })?;
+            assets.insert(&full_path, image)
+        }
+    };
+    Ok(Sprite { path: path.to_owned(), image })
+}
+
+fn spawn_entity(
+    world: &mut World,
+    data: &EntityData,
+    sprite: Option<Sprite>,
+) -> Entity {
+    let entity = world.spawn().id();
+    if let Some(name) = &data.name {
+        world.insert(entity, Name(name.clone()));
+    }
+    if let Some(pos) = data.position {
+        world.insert(entity, pos);
+        world.insert(entity, PreviousPosition { x: pos.x, y: pos.y });
+    }
+    if let Some(rotation) = data.rotation {
+        world.insert(entity, Rotation(rotation));
+    }
+    if let Some(velocity) = data.velocity {
+        world.insert(entity, velocity);
+    }
+    if let Some(angular_velocity) = data.angular_velocity {
+        world.insert(entity, AngularVelocity(angular_velocity));
+    }
+    if let Some(transform) = data.transform {
+        world.insert(entity, LocalTransform(transform));
+    }
+    if let Some(bounds) = data.bounds {
+        world.insert(entity, bounds);
+    }
+    if let Some(color) = data.fill {
+        world.insert(entity, Fill(color));
+    }
+    if let Some(sprite) = sprite {
+        world.insert(entity, sprite);
+    }
+    if let Some(shape) = data.collider {
+        world.insert(entity, Collider(shape));
+    }
+    if let Some(shape) = data.drawable {
+        world.insert(entity, Drawable(shape));
+    }
+    if let Some(rigid_body) = data.rigid_body {
+        world.insert(entity, rigid_body);
+    }
+    if let Some(player) = data.player {
+        world.insert(entity, player);
+    }
+    if let Some(label) = &data.label {
+        world.insert(entity, label.clone());
+    }
+    entity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: &str = r#"(
+        version: 1,
+        entities: [
+            (
+                name: "tank",
+                position: (x: 10, y: 20),
+                collider: Aabb(min: (x: -4, y: -2), max: (x: 4, y: 2)),
+                rigid_body: (mass: 2),
+            ),
+            (
+                parent: "tank",
+                transform: (translation: (x: 0, y: -3), rotation: 0.5),
+                drawable: Circle(center: (x: 0, y: 0), radius: 1.5),
+                fill: (r: 255, g: 0, b: 0),
+            ),
+        ],
+    )"#;
+
+    #[test]
+    fn spawns_components_and_parents() {
+        let scene = Scene::parse(SCENE, SceneFormat::Ron).unwrap();
+        let mut world = World::new();
+
+        let [tank, turret] = scene.spawn(&mut world, ".").unwrap()[..] else {
+            panic!("expected two entities");
+        };
+
+        assert_eq!(
+            *world.get::<Position>(tank).unwrap(),
+            Position { x: 10.0, y: 20.0 }
+        );
+        assert_eq!(
+            world
+                .get::<RigidBody>(tank)
+                .unwrap()
+                .friction,
+            0.5
+        );
+        assert_eq!(world.get::<Parent>(turret).unwrap().0, tank);
+        assert_eq!(world.get::<Fill>(turret).unwrap().0, Color::RED);
+        assert_eq!(
+            world
+                .get::<LocalTransform>(turret)
+                .unwrap()
+                .0
+                .scale,
+            Transform2D::IDENTITY.scale
+        );
+    }
+
+    #[test]
+    fn round_trips_through_both_formats() {
+        let scene = Scene::parse(SCENE, SceneFormat::Ron).unwrap();
+        let mut world = World::new();
+        scene.spawn(&mut world, ".").unwrap();
+        let captured = Scene::capture(&world);
+
+        for format in [SceneFormat::Ron, SceneFormat::Json] {
+            let source = captured.to_string(format).unwrap();
+            assert_eq!(Scene::parse(&source, format).unwrap(), captured);
+        }
+        assert_eq!(captured.entities, scene.entities);
+    }
+
+    #[test]
+    fn reports_malformed_scenes() {
+        let error = |source: &str, format| {
+            Scene::parse(source, format)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            error("(version: 2)", SceneFormat::Ron),
+            "scene version 2 is newer than the supported version 1"
+        );
+        let typo =
+            error("(version: 1, entities: [(positon: ())])", SceneFormat::Ron);
+        assert!(typo.starts_with("RON scene 1:26: "), "{typo}");
+        assert!(typo.contains("positon"), "{typo}");
+        assert!(
+            error(
+                r#"{"version": 1, "entities": [{"bounds": 3}]}"#,
+                SceneFormat::Json
+            )
+            .starts_with("JSON scene 1:")
+        );
+
+        let scene = Scene {
+            entities: vec![EntityData {
+                parent: Some("nobody".into()),
+                ..EntityData::default()
+            }],
+            ..Scene::default()
+        };
+        let mut world = World::new();
+        assert_eq!(
+            scene
+                .spawn(&mut world, ".")
+                .unwrap_err()
+                .to_string(),
+            "entity 0 has parent \"nobody\", but no entity has that name"
+        );
+        assert!(world.is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+//! Decoded audio clips.
+//!
+//! Only RIFF WAVE files are read: 8, 16 and 24-bit integer PCM and
+//! 32-bit float samples.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+#[derive(Debug)]
+pub enum SoundError {
+    Io(io::Error),
+    UnsupportedFormat,
+    Malformed(String),
+}
+
+impl fmt::Display for SoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read sound: {err}"),
+            Self::UnsupportedFormat => write!(f, "unsupported sound format"),
+            Self::Malformed(message) => {
+                write!(f, "malformed WAV sound: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SoundError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SoundError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Interleaved samples in `-1.0..=1.0`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sound {
+    sample_rate: u32,
+    channels: u16,
+    samples: Vec<f32>,
+}
+
+impl Sound {
+    /// Wraps interleaved samples; `samples.len()` must be a multiple of
+    /// `channels`.
+    pub fn from_samples(
+        sample_rate: u32,
+        channels: u16,
+        samples: Vec<f32>,
+    ) -> Self {
+        assert!(channels > 0, "a sound needs at least one channel");
+        assert_eq!(
+            samples.len() % channels as usize,
+            0,
+            "sample count is not a multiple of the channel count"
+        );
+        Self { sample_rate, channels, samples }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SoundError> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, SoundError> {
+        if bytes.len() < 12
+            || &bytes[..4] != b"RIFF"
+            || &bytes[8..12] != b"WAVE"
+        {
+            return Err(SoundError::UnsupportedFormat);
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut at = 12;
+        while at + 8 <= bytes.len() {
+            let id = &bytes[at..at + 4];
+            let len = read_u32(bytes, at + 4)? as usize;
+            let body = bytes
+                .get(at + 8..at + 8 + len)
+                .ok_or_else(|| malformed("chunk runs past the end"))?;
+            match id {
+                b"fmt " => format = Some(Format::parse(body)?),
+                b"data" => data = Some(body),
+                _ => {}
+            }
+            // Chunks are padded to an even length.
+            at += 8 + len + (len & 1);
+        }
+        let format = format.ok_or_else(|| malformed("missing fmt chunk"))?;
+        let data = data.ok_or_else(|| malformed("missing data chunk"))?;
+
+        let frame_len = format.channels as usize * format.bytes_per_sample();
+        let samples = data[..data.len() - data.len() % frame_len]
+            .chunks_exact(format.bytes_per_sample())
+            .map(|sample| format.sample(sample))
+            .collect();
+        Ok(Self::from_samples(format.sample_rate, format.channels, samples))
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// Samples per channel.
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    pub fn duration_secs(&self) -> f64 {
+        self.frames() as f64 / self.sample_rate as f64
+    }
+}
+
+struct Format {
+    float: bool,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+impl Format {
+    fn parse(body: &[u8]) -> Result<Self, SoundError> {
+        let mut tag = read_u16(body, 0)?;
+        let channels = read_u16(body, 2)?;
+        let sample_rate = read_u32(body, 4)?;
+        let bits_per_sample = read_u16(body, 14)?;
+        if tag == WAVE_FORMAT_EXTENSIBLE {
+            // The real format leads the subformat GUID.
+            tag = read_u16(body, 24)?;
+        }
+        if channels == 0 || sample_rate == 0 {
+            return Err(malformed("no channels or zero sample rate"));
+        }
+        let float = match (tag, bits_per_sample) {
+            (WAVE_FORMAT_PCM, 8 | 16 | 24) => false,
+            (WAVE_FORMAT_IEEE_FLOAT, 32) => true,
+            _ => {
+                return Err(malformed(format!(
+                    "format {tag} with {bits_per_sample}-bit samples is \
+                     not supported"
+                )));
+            }
+        };
+        Ok(Self {
+            float,
+            channels,
+            sample_rate,
+            bits_per_sample,
+        })
+    }
+
+    fn bytes_per_sample(&self) -> usize {
+        self.bits_per_sample as usize / 8
+    }
+
+    fn sample(&self, bytes: &[u8]) -> f32 {
+        match (self.float, bytes) {
+            (true, &[a, b, c, d]) => f32::from_le_bytes([a, b, c, d]),
+            // 8-bit samples alone are unsigned.
+            (false, &[a]) => (a as f32 - 128.0) / 128.0,
+            (false, &[a, b]) => i16::from_le_bytes([a, b]) as f32 / 32768.0,
+            (false, &[a, b, c]) => {
+                (i32::from_le_bytes([0, a, b, c]) >> 8) as f32 / 8388608.0
+            }
+            _ => unreachable!("sample sizes are checked in Format::parse"),
+        }
+    }
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Result<u16, SoundError> {
+    bytes
+        .get(at..at + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| malformed("truncated header"))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Result<u32, SoundError> {
+    bytes
+        .get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| malformed("truncated header"))
+}
+
+fn malformed(message: impl Into<String>) -> SoundError {
+    SoundError::Malformed(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(tag: u16, channels: u16, bits: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&tag.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&22050u32.to_le_bytes());
+        let block = channels * bits / 8;
+        bytes.extend_from_slice(&(22050 * block as u32).to_le_bytes());
+        bytes.extend_from_slice(&block.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn decodes_pcm_and_float_samples() {
+        let data: Vec<u8> = [0i16, i16::MIN, 16384, -16384]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let sound = Sound::decode(&wav(1, 2, 16, &data)).unwrap();
+        assert_eq!(sound.sample_rate(), 22050);
+        assert_eq!(sound.frames(), 2);
+        assert_eq!(sound.samples(), [0.0, -1.0, 0.5, -0.5]);
+
+        let sound = Sound::decode(&wav(1, 1, 8, &[128, 0, 192])).unwrap();
+        assert_eq!(sound.samples(), [0.0, -1.0, 0.5]);
+
+        let sound =
+            Sound::decode(&wav(3, 1, 32, &0.25f32.to_le_bytes())).unwrap();
+        assert_eq!(sound.samples(), [0.25]);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(matches!(
+            Sound::decode(b"OggS\0\0\0\0\0\0\0\0"),
+            Err(SoundError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            Sound::decode(&wav(2, 1, 4, &[0])),
+            Err(SoundError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,232 @@
+//! Systems and resources that move entities around the window.
+
+use crate::camera::Camera2D;
+use crate::collision::{SpatialHash, collide};
+use crate::components::{
+    Bounds, Collider, GlobalTransform, PlayerControlled, Position,
+    PreviousPosition, RigidBody, Velocity,
+};
+use crate::ecs::{Entity, Schedule, World};
+use crate::hierarchy::{root_transform, world_transforms};
+use crate::input::actions::{MOVE_X, MOVE_Y, TELEPORT};
+use crate::input::{InputMap, InputState};
+use crate::math::Vec2;
+use crate::physics::step_physics;
+
+/// Length of the current simulation tick.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Time {
+    pub dt: f64,
+}
+
+/// Area that entities with [`Bounds`] bounce around inside.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Arena {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The systems run by every fixed tick, in order.
+pub fn motion_schedule() -> Schedule {
+    Schedule::new()
+        .with_system("store_previous_positions", store_previous_positions)
+        .with_system("apply_player_input", apply_player_input)
+        .with_system("step_physics", step_physics)
+        .with_system("integrate_velocity", integrate_velocity)
+        .with_system("collide_bodies", collide_bodies)
+        .with_system("bounce_off_edges", bounce_off_edges)
+        .with_system("propagate_transforms", propagate_transforms)
+}
+
+pub fn store_previous_positions(world: &mut World) {
+    world.query::<(&Position, &mut PreviousPosition)>(|_, (pos, prev)| {
+        *prev = PreviousPosition { x: pos.x, y: pos.y };
+    });
+}
+
+/// Steers [`PlayerControlled`] entities from the [`InputState`] and
+/// [`InputMap`] resources. Teleports go to the cursor's world position
+/// under the [`Camera2D`], if there is one.
+pub fn apply_player_input(world: &mut World) {
+    let (Some(input), Some(map), Some(time)) = (
+        world.resource::<InputState>(),
+        world.resource::<InputMap>(),
+        world.resource::<Time>(),
+    ) else {
+        return;
+    };
+    let axis_x = input.axis(&map, MOVE_X) as f64;
+    let axis_y = input.axis(&map, MOVE_Y) as f64;
+    let teleport = input.action_just_pressed(&map, TELEPORT);
+    let (cursor_x, cursor_y) = input.cursor();
+    let cursor = world
+        .resource::<Camera2D>()
+        .map_or(Vec2::new(cursor_x, cursor_y), |camera| {
+            camera.screen_to_world(Vec2::new(cursor_x, cursor_y))
+        });
+
+    world.query::<(
+        &mut Position,
+        Option<&mut PreviousPosition>,
+        Option<&Bounds>,
+        &PlayerControlled,
+    )>(|_, (pos, prev, bounds, player)| {
+        if teleport {
+            let bounds = bounds.copied().unwrap_or_default();
+            pos.x = cursor.x - bounds.w / 2.0;
+            pos.y = cursor.y - bounds.h / 2.0;
+            if let Some(prev) = prev {
+                *prev = PreviousPosition { x: pos.x, y: pos.y };
+            }
+        }
+        pos.x += axis_x * player.speed * time.dt;
+        pos.y += axis_y * player.speed * time.dt;
+    });
+}
+
+/// Moves entities by their [`Velocity`] over [`Time::dt`].
+pub fn integrate_velocity(world: &mut World) {
+    let Some(dt) = world
+        .resource::<Time>()
+        .map(|time| time.dt)
+    else {
+        return;
+    };
+    world.query::<(&mut Position, &Velocity)>(|_, (pos, vel)| {
+        pos.x += vel.x * dt;
+        pos.y += vel.y * dt;
+    });
+}
+
+/// Pushes overlapping [`Collider`]s apart and bounces them off each
+/// other as equal-mass elastic bodies. Candidate pairs come from a
+/// [`SpatialHash`] sized to the largest collider. Entities with a
+/// [`RigidBody`] are left to [`step_physics`].
+pub fn collide_bodies(world: &mut World) {
+    let mut bodies: Vec<(Entity, Collider, Vec2)> = Vec::new();
+    world.query::<(&Position, &Collider, Option<&RigidBody>)>(
+        |entity, (pos, collider, rigid)| {
+            if rigid.is_none() {
+                bodies.push((entity, *collider, Vec2::new(pos.x, pos.y)));
+            }
+        },
+    );
+    let cell_size = bodies
+        .iter()
+        .map(|(_, collider, _)| {
+            let size = collider.0.bounds().half_extents() * 2.0;
+            size.x.max(size.y)
+        })
+        .fold(1.0, f64::max);
+
+    let mut grid = SpatialHash::new(cell_size);
+    for (index, (_, collider, pos)) in bodies.iter().enumerate() {
+        grid.insert(index, collider.0.translated(*pos).bounds());
+    }
+
+    for (i, j) in grid.pairs() {
+        let (a, b) = (bodies[i], bodies[j]);
+        let Some(contact) =
+            collide(&a.1.0.translated(a.2), &b.1.0.translated(b.2))
+        else {
+            continue;
+        };
+
+        let velocity = |entity| {
+            world
+                .get::<Velocity>(entity)
+                .map(|v| *v)
+        };
+        let (vel_a, vel_b) = (velocity(a.0), velocity(b.0));
+        let moving = (vel_a.is_some(), vel_b.is_some());
+        let share_a = match moving {
+            (true, true) => 0.5,
+            (true, false) => 1.0,
+            (false, true) => 0.0,
+            (false, false) => continue,
+        };
+        bodies[i].2 -= contact.normal * (contact.depth * share_a);
+        bodies[j].2 += contact.normal * (contact.depth * (1.0 - share_a));
+
+        let to_vec =
+            |v: Option<Velocity>| v.map_or(Vec2::ZERO, |v| Vec2::new(v.x, v.y));
+        let (va, vb) = (to_vec(vel_a), to_vec(vel_b));
+        let closing = (vb - va).dot(contact.normal);
+        if closing < 0.0 {
+            // Equal masses swap normal velocity; a wall reflects it.
+            let (dva, dvb) = match moving {
+                (true, true) => {
+                    (contact.normal * closing, -contact.normal * closing)
+                }
+                (true, false) => (contact.normal * (2.0 * closing), Vec2::ZERO),
+                _ => (Vec2::ZERO, -contact.normal * (2.0 * closing)),
+            };
+            for (entity, delta) in [(a.0, dva), (b.0, dvb)] {
+                if let Some(mut vel) = world.get_mut::<Velocity>(entity) {
+                    vel.x += delta.x;
+                    vel.y += delta.y;
+                }
+            }
+        }
+    }
+
+    for (entity, _, pos) in bodies {
+        if let Some(mut position) = world.get_mut::<Position>(entity) {
+            *position = Position { x: pos.x, y: pos.y };
+        }
+    }
+}
+
+/// Keeps entities' [`Bounds`] inside the [`Arena`], reversing velocity on
+/// any axis where they hit an edge while moving outwards.
+pub fn bounce_off_edges(world: &mut World) {
+    let Some(arena) = world
+        .resource::<Arena>()
+        .map(|arena| *arena)
+    else {
+        return;
+    };
+    world.query::<(&mut Position, &mut Velocity, &Bounds)>(
+        |_, (pos, vel, bounds)| {
+            bounce_axis(&mut pos.x, &mut vel.x, arena.width - bounds.w);
+            bounce_axis(&mut pos.y, &mut vel.y, arena.height - bounds.h);
+        },
+    );
+}
+
+/// Updates the [`GlobalTransform`] of every entity in the hierarchy from
+/// its ancestors' current placement.
+pub fn propagate_transforms(world: &mut World) {
+    let transforms =
+        world_transforms(world, |entity| root_transform(world, entity));
+    for (entity, transform) in transforms {
+        world.insert(entity, GlobalTransform(transform));
+    }
+}
+
+fn bounce_axis(pos: &mut f64, vel: &mut f64, max: f64) {
+    let max = max.max(0.0);
+    if (*pos <= 0.0 && *vel < 0.0) || (*pos >= max && *vel > 0.0) {
+        *vel = -*vel;
+    }
+    *pos = pos.clamp(0.0, max);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounces_only_when_moving_outwards() {
+        let (mut pos, mut vel) = (12.0, 5.0);
+        bounce_axis(&mut pos, &mut vel, 10.0);
+        assert_eq!((pos, vel), (10.0, -5.0));
+
+        bounce_axis(&mut pos, &mut vel, 10.0);
+        assert_eq!((pos, vel), (10.0, -5.0));
+
+        let (mut pos, mut vel) = (-1.0, -5.0);
+        bounce_axis(&mut pos, &mut vel, 10.0);
+        assert_eq!((pos, vel), (0.0, 5.0));
+    }
+}
@@ -0,0 +1,312 @@
+//! Text layout and drawing for any [`Font`].
+
+use crate::color::Color;
+use crate::framebuffer::Framebuffer;
+
+/// A source of glyphs that text can be laid out with.
+///
+/// Metrics are in framebuffer pixels at the given integer `scale`.
+pub trait Font {
+    fn line_height(&self, scale: u32) -> i32;
+
+    fn advance(&self, ch: char, scale: u32) -> i32;
+
+    /// Extra horizontal adjustment between a pair of characters.
+    fn kerning(&self, _left: char, _right: char, _scale: u32) -> i32 {
+        0
+    }
+
+    /// Draws `ch` with the top of its line box at `(x, y)`.
+    fn draw_glyph(
+        &self,
+        framebuffer: &mut Framebuffer,
+        ch: char,
+        x: i32,
+        y: i32,
+        scale: u32,
+        color: Color,
+    );
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextStyle {
+    pub color: Color,
+    /// Integer magnification applied to the font's glyphs.
+    pub scale: u32,
+    /// Lines are aligned within `max_width` when set, otherwise around
+    /// the anchor `x` (centered on it, or ending at it).
+    pub align: Align,
+    /// Word-wraps lines that would grow wider than this many pixels.
+    pub max_width: Option<i32>,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            scale: 1,
+            align: Align::Left,
+            max_width: None,
+        }
+    }
+}
+
+impl Framebuffer {
+    /// Draws `text` with the top-left of its first line at `(x, y)`.
+    /// `'\n'` starts a new line.
+    pub fn draw_text(
+        &mut self,
+        font: &impl Font,
+        text: &str,
+        x: i32,
+        y: i32,
+        style: &TextStyle,
+    ) {
+        let scale = style.scale.max(1);
+        let line_height = font.line_height(scale);
+        let box_width = style.max_width.unwrap_or(0);
+
+        for (i, line) in layout(font, text, style)
+            .into_iter()
+            .enumerate()
+        {
+            let width = line_width(font, line, scale);
+            let mut pen_x = x + match style.align {
+                Align::Left => 0,
+                Align::Center => (box_width - width) / 2,
+                Align::Right => box_width - width,
+            };
+            let pen_y = y + i as i32 * line_height;
+
+            let mut prev = None;
+            for ch in line.chars() {
+                if let Some(prev) = prev {
+                    pen_x += font.kerning(prev, ch, scale);
+                }
+                font.draw_glyph(self, ch, pen_x, pen_y, scale, style.color);
+                pen_x += font.advance(ch, scale);
+                prev = Some(ch);
+            }
+        }
+    }
+}
+
+/// Width and height in pixels that [`Framebuffer::draw_text`] would cover.
+pub fn measure_text(
+    font: &impl Font,
+    text: &str,
+    style: &TextStyle,
+) -> (i32, i32) {
+    let scale = style.scale.max(1);
+    let lines = layout(font, text, style);
+    let width = lines
+        .iter()
+        .map(|line| line_width(font, line, scale))
+        .max()
+        .unwrap_or(0);
+
+    (width, lines.len() as i32 * font.line_height(scale))
+}
+
+/// Splits text into lines on `'\n'` and, with `max_width`, at word
+/// boundaries. Words too long for a line on their own are broken.
+fn layout<'a>(
+    font: &impl Font,
+    text: &'a str,
+    style: &TextStyle,
+) -> Vec<&'a str> {
+    let scale = style.scale.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let Some(max_width) = style.max_width else {
+            lines.push(paragraph);
+            continue;
+        };
+        // Below zero even an empty remainder wouldn't fit.
+        let max_width = max_width.max(0);
+
+        let first_line = lines.len();
+        let (mut start, mut end, mut word_start) = (0, 0, 0);
+        loop {
+            let word_end = paragraph[word_start..]
+                .find(' ')
+                .map_or(paragraph.len(), |i| word_start + i);
+
+            if line_width(font, &paragraph[start..word_end], scale) <= max_width
+            {
+                end = word_end;
+            } else if end > start {
+                lines.push(&paragraph[start..end]);
+                (start, end) = (word_start, word_start);
+                continue;
+            } else {
+                let split = start
+                    + fitting_prefix(
+                        font,
+                        &paragraph[start..word_end],
+                        max_width,
+                        scale,
+                    );
+                lines.push(&paragraph[start..split]);
+                if split == word_end && word_end < paragraph.len() {
+                    // Like a wrap, drop the space after the broken word.
+                    word_start = word_end + 1;
+                    (start, end) = (word_start, word_start);
+                } else {
+                    (start, end) = (split, split);
+                }
+                continue;
+            }
+
+            if word_end == paragraph.len() {
+                break;
+            }
+            word_start = word_end + 1;
+        }
+        if end > start || lines.len() == first_line {
+            lines.push(&paragraph[start..end]);
+        }
+    }
+
+    lines
+}
+
+fn line_width(font: &impl Font, line: &str, scale: u32) -> i32 {
+    let mut width = 0;
+    let mut prev = None;
+    for ch in line.chars() {
+        if let Some(prev) = prev {
+            width += font.kerning(prev, ch, scale);
+        }
+        width += font.advance(ch, scale);
+        prev = Some(ch);
+    }
+    width
+}
+
+/// Byte length of the longest prefix that fits, and at least one char.
+fn fitting_prefix(
+    font: &impl Font,
+    word: &str,
+    max_width: i32,
+    scale: u32,
+) -> usize {
+    let mut fit = 0;
+    for (i, ch) in word.char_indices() {
+        let end = i + ch.len_utf8();
+        if fit > 0 && line_width(font, &word[..end], scale) > max_width {
+            break;
+        }
+        fit = end;
+    }
+    fit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every glyph is a 2x3 block advancing 3 pixels.
+    struct BlockFont;
+
+    impl Font for BlockFont {
+        fn line_height(&self, scale: u32) -> i32 {
+            4 * scale as i32
+        }
+
+        fn advance(&self, _ch: char, scale: u32) -> i32 {
+            3 * scale as i32
+        }
+
+        fn draw_glyph(
+            &self,
+            framebuffer: &mut Framebuffer,
+            ch: char,
+            x: i32,
+            y: i32,
+            scale: u32,
+            color: Color,
+        ) {
+            if ch != ' ' {
+                let s = scale as i32;
+                framebuffer.fill_rect(x, y, 2 * s, 3 * s, color);
+            }
+        }
+    }
+
+    fn style(max_width: Option<i32>) -> TextStyle {
+        TextStyle { max_width, ..TextStyle::default() }
+    }
+
+    #[test]
+    fn measures_lines_and_scale() {
+        let scaled = TextStyle { scale: 2, ..TextStyle::default() };
+
+        assert_eq!(measure_text(&BlockFont, "abc\nde", &style(None)), (9, 8));
+        assert_eq!(measure_text(&BlockFont, "ab", &scaled), (12, 8));
+    }
+
+    #[test]
+    fn wraps_at_word_boundaries() {
+        let lines = layout(&BlockFont, "aa bb cc\ndd", &style(Some(15)));
+
+        assert_eq!(lines, ["aa bb", "cc", "dd"]);
+    }
+
+    #[test]
+    fn breaks_words_longer_than_a_line() {
+        let lines = layout(&BlockFont, "abcdefg hi", &style(Some(9)));
+
+        assert_eq!(lines, ["abc", "def", "g", "hi"]);
+        let lines = layout(&BlockFont, "abcdef gh", &style(Some(9)));
+        assert_eq!(lines, ["abc", "def", "gh"]);
+    }
+
+    #[test]
+    fn narrow_widths_put_one_char_per_line() {
+        for max_width in [-1, 0, 2] {
+            let narrow = style(Some(max_width));
+
+            assert_eq!(layout(&BlockFont, "ab c", &narrow), ["a", "b", "c"]);
+            assert_eq!(layout(&BlockFont, "ab", &narrow), ["a", "b"]);
+            assert_eq!(layout(&BlockFont, "", &narrow), [""]);
+            assert_eq!(measure_text(&BlockFont, "ab", &narrow), (3, 8));
+        }
+    }
+
+    #[test]
+    fn aligns_within_max_width() {
+        let mut fb = Framebuffer::new(12, 4);
+        let right = TextStyle {
+            align: Align::Right,
+            max_width: Some(12),
+            ..TextStyle::default()
+        };
+        fb.draw_text(&BlockFont, "a", 0, 0, &right);
+
+        assert_eq!(fb.pixel(9, 0), Some(0x00FFFFFF));
+        assert_eq!(fb.pixel(8, 0), Some(0));
+    }
+
+    #[test]
+    fn centers_on_anchor_without_max_width() {
+        let mut fb = Framebuffer::new(12, 4);
+        let centered = TextStyle {
+            align: Align::Center,
+            ..TextStyle::default()
+        };
+        fb.draw_text(&BlockFont, "ab", 6, 0, &centered);
+
+        assert_eq!(fb.pixel(3, 0), Some(0x00FFFFFF));
+        assert_eq!(fb.pixel(2, 0), Some(0));
+    }
+}
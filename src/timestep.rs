@@ -0,0 +1,102 @@
+/// Accumulator that converts variable frame times into fixed simulation
+/// ticks.
+///
+/// Each frame, feed the elapsed wall-clock time to [`advance`], run the
+/// returned number of `update(dt)` steps, then render with [`alpha`] to
+/// interpolate between the last two simulation states.
+///
+/// [`advance`]: FixedTimestep::advance
+/// [`alpha`]: FixedTimestep::alpha
+#[derive(Clone, Debug)]
+pub struct FixedTimestep {
+    dt: f64,
+    max_steps: u32,
+    accumulator: f64,
+}
+
+impl FixedTimestep {
+    pub const DEFAULT_MAX_STEPS: u32 = 5;
+
+    /// Creates a timestep running `tick_rate` updates per second.
+    pub fn new(tick_rate: f64) -> Self {
+        assert!(tick_rate > 0.0, "tick rate must be positive");
+
+        Self {
+            dt: 1.0 / tick_rate,
+            max_steps: Self::DEFAULT_MAX_STEPS,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Limits how many ticks a single frame may run to catch up. Time
+    /// beyond that is dropped so a long stall cannot spiral.
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps.max(1);
+        self
+    }
+
+    /// Seconds simulated by each tick.
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    pub fn tick_rate(&self) -> f64 {
+        1.0 / self.dt
+    }
+
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps
+    }
+
+    /// Adds `frame_time` seconds and returns how many ticks to run.
+    pub fn advance(&mut self, frame_time: f64) -> u32 {
+        self.accumulator += frame_time.max(0.0);
+
+        let steps = (self.accumulator / self.dt).floor() as u64;
+        if steps > self.max_steps as u64 {
+            self.accumulator %= self.dt;
+            return self.max_steps;
+        }
+
+        self.accumulator -= steps as f64 * self.dt;
+        steps as u32
+    }
+
+    /// Fraction of a tick left in the accumulator, in `[0, 1)`.
+    pub fn alpha(&self) -> f64 {
+        (self.accumulator / self.dt).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_whole_ticks_and_keeps_remainder() {
+        let mut timestep = FixedTimestep::new(4.0);
+
+        assert_eq!(timestep.advance(0.625), 2);
+        assert_eq!(timestep.alpha(), 0.5);
+
+        assert_eq!(timestep.advance(0.125), 1);
+        assert_eq!(timestep.alpha(), 0.0);
+    }
+
+    #[test]
+    fn clamps_catch_up_steps() {
+        let mut timestep = FixedTimestep::new(60.0).with_max_steps(3);
+
+        assert_eq!(timestep.advance(1.0), 3);
+        assert!(timestep.alpha() < 1.0);
+        assert_eq!(timestep.advance(0.0), 0);
+    }
+
+    #[test]
+    fn ignores_negative_frame_times() {
+        let mut timestep = FixedTimestep::new(60.0);
+
+        assert_eq!(timestep.advance(-1.0), 0);
+        assert_eq!(timestep.alpha(), 0.0);
+    }
+}
@@ -0,0 +1,138 @@
+//! Translation, rotation and scale in 2D, composed parent to child.
+
+use serde::{Deserialize, Serialize};
+
+use crate::collision::{Aabb, Circle, Obb, Shape};
+use crate::math::Vec2;
+
+/// Scales, then rotates clockwise by `rotation` radians, then
+/// translates. Omitted fields deserialize as the identity's.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Transform2D {
+    pub translation: Vec2,
+    pub rotation: f64,
+    pub scale: Vec2,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform2D {
+    pub const IDENTITY: Self = Self {
+        translation: Vec2::ZERO,
+        rotation: 0.0,
+        scale: Vec2::new(1.0, 1.0),
+    };
+
+    pub fn from_translation(translation: Vec2) -> Self {
+        Self { translation, ..Self::IDENTITY }
+    }
+
+    pub fn with_rotation(self, rotation: f64) -> Self {
+        Self { rotation, ..self }
+    }
+
+    pub fn with_scale(self, scale: Vec2) -> Self {
+        Self { scale, ..self }
+    }
+
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        point
+            .mul_elements(self.scale)
+            .rotate(self.rotation)
+            + self.translation
+    }
+
+    pub fn transform_vector(&self, vector: Vec2) -> Vec2 {
+        vector
+            .mul_elements(self.scale)
+            .rotate(self.rotation)
+    }
+
+    /// `child` expressed in this transform's parent space. Rotations and
+    /// scales combine independently, so a non-uniformly scaled parent
+    /// does not shear its rotated children.
+    pub fn then(&self, child: &Self) -> Self {
+        Self {
+            translation: self.transform_point(child.translation),
+            rotation: self.rotation + child.rotation,
+            scale: self.scale.mul_elements(child.scale),
+        }
+    }
+
+    /// `shape` moved from local into this transform's space. Circles
+    /// take the larger scale so they stay round.
+    pub fn transform_shape(&self, shape: &Shape) -> Shape {
+        let scaled = match *shape {
+            Shape::Aabb(aabb) => {
+                let (a, b) = (
+                    aabb.min.mul_elements(self.scale),
+                    aabb.max.mul_elements(self.scale),
+                );
+                Shape::Aabb(Aabb::new(a.min(b), a.max(b)))
+            }
+            Shape::Circle(circle) => Shape::Circle(Circle::new(
+                circle.center.mul_elements(self.scale),
+                circle.radius
+                    * self
+                        .scale
+                        .x
+                        .abs()
+                        .max(self.scale.y.abs()),
+            )),
+            Shape::Obb(obb) => Shape::Obb(Obb::new(
+                obb.center.mul_elements(self.scale),
+                obb.half_extents
+                    .mul_elements(self.scale.abs()),
+                obb.rotation,
+            )),
+        };
+        scaled.transformed(self.translation, self.rotation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!((a - b).length() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn composes_parent_then_child() {
+        let tank = Transform2D::from_translation(Vec2::new(100.0, 50.0))
+            .with_rotation(FRAC_PI_2)
+            .with_scale(Vec2::new(2.0, 2.0));
+        let turret = Transform2D::from_translation(Vec2::new(10.0, 0.0))
+            .with_rotation(FRAC_PI_2);
+
+        let world = tank.then(&turret);
+
+        assert_near(world.translation, Vec2::new(100.0, 70.0));
+        assert_eq!(world.rotation, 2.0 * FRAC_PI_2);
+        assert_eq!(world.scale, Vec2::new(2.0, 2.0));
+        assert_near(
+            world.transform_point(Vec2::new(1.0, 0.0)),
+            Vec2::new(98.0, 70.0),
+        );
+    }
+
+    #[test]
+    fn scales_shapes_before_placing_them() {
+        let transform = Transform2D::from_translation(Vec2::new(5.0, 5.0))
+            .with_scale(Vec2::new(2.0, -1.0));
+        let square = Shape::Aabb(Aabb::new(Vec2::ZERO, Vec2::new(1.0, 1.0)));
+
+        assert_eq!(
+            transform.transform_shape(&square),
+            Shape::Aabb(Aabb::new(Vec2::new(5.0, 4.0), Vec2::new(7.0, 5.0)))
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use window_app::assets::{AssetEvent, AssetWatcher};
+use window_app::color::Color;
+use window_app::components::Sprite;
+use window_app::framebuffer::Framebuffer;
+use window_app::graphics::GraphicsState;
+use window_app::ppm;
+use window_app::scene::{EntityData, Scene};
+
+fn write_image(path: &Path, color: Color) {
+    let mut framebuffer = Framebuffer::new(2, 2);
+    framebuffer.fill(color);
+    ppm::save(path, &framebuffer).unwrap();
+}
+
+#[test]
+fn scene_sprites_follow_their_files() {
+    let dir = env::temp_dir().join("window_app_scene_sprites");
+    fs::create_dir_all(&dir).unwrap();
+    write_image(&dir.join("sprite.ppm"), Color::RED);
+    let scene = Scene {
+        entities: vec![EntityData {
+            sprite: Some("sprite.ppm".into()),
+            ..EntityData::default()
+        }],
+        ..Scene::default()
+    };
+    let mut state = GraphicsState::new(16, 16);
+    let spawned = scene
+        .spawn(&mut state.world, &dir)
+        .unwrap();
+    let handle = state
+        .world
+        .get::<Sprite>(spawned[0])
+        .unwrap()
+        .image
+        .clone();
+
+    write_image(&dir.join("sprite.ppm"), Color::GREEN);
+    let events = state.reload_asset(dir.join("sprite.ppm"));
+
+    assert!(matches!(events[..], [AssetEvent::Reloaded { .. }]));
+    assert_eq!(events[0].handle(), handle);
+    let assets = state.assets();
+    assert_eq!(assets.get(&handle).unwrap().pixel(1, 1), Some(Color::GREEN));
+}
+
+#[test]
+fn scene_sprites_share_images_until_despawned() {
+    let dir = env::temp_dir().join("window_app_shared_sprites");
+    fs::create_dir_all(&dir).unwrap();
+    write_image(&dir.join("shared.ppm"), Color::RED);
+    let sprite = EntityData {
+        sprite: Some("shared.ppm".into()),
+        ..EntityData::default()
+    };
+    let scene = Scene {
+        entities: vec![sprite.clone(), sprite],
+        ..Scene::default()
+    };
+    let mut state = GraphicsState::new(16, 16);
+    let spawned = scene
+        .spawn(&mut state.world, &dir)
+        .unwrap();
+    let images: Vec<_> = spawned
+        .iter()
+        .map(|&entity| {
+            state
+                .world
+                .get::<Sprite>(entity)
+                .unwrap()
+                .image
+                .clone()
+        })
+        .collect();
+    assert_eq!(images[0], images[1]);
+    drop(images);
+
+    state.update_assets();
+    assert_eq!(state.assets().len(), 1);
+    for entity in spawned {
+        state.world.despawn(entity);
+    }
+    state.update_assets();
+    assert!(state.assets().is_empty());
+}
+
+#[test]
+fn watcher_reports_changed_files() {
+    let dir = env::temp_dir().join("window_app_watcher");
+    fs::create_dir_all(&dir).unwrap();
+    let watched = dir.join("watched.ppm");
+    let ignored = dir.join("ignored.ppm");
+    write_image(&watched, Color::RED);
+
+    let (sender, changes) = mpsc::channel();
+    let mut watcher = AssetWatcher::new(move |path| {
+        let _ = sender.send(path);
+    })
+    .unwrap();
+    watcher.watch(&watched).unwrap();
+    assert!(watcher.is_watching(&watched));
+
+    write_image(&ignored, Color::BLUE);
+    write_image(&watched, Color::GREEN);
+
+    let changed = changes
+        .recv_timeout(Duration::from_secs(10))
+        .expect("no change reported");
+    assert_eq!(changed, fs::canonicalize(&watched).unwrap());
+    while let Ok(changed) = changes.recv_timeout(Duration::from_millis(200)) {
+        assert_eq!(changed, fs::canonicalize(&watched).unwrap());
+    }
+}
@@ -0,0 +1,239 @@
+use winit::event::MouseButton;
+use winit::keyboard::KeyCode;
+
+use window_app::collision::{Aabb, Shape};
+use window_app::color::Color;
+use window_app::components::{
+    Bounds, Collider, Fill, Position, PreviousPosition, Velocity,
+};
+use window_app::golden::{Tolerance, assert_golden};
+use window_app::graphics::GraphicsState;
+use window_app::image::Image;
+use window_app::input::Button;
+use window_app::math::Vec2;
+
+const WIDTH: usize = 160;
+const HEIGHT: usize = 120;
+const DT: f64 = 1.0 / 60.0;
+
+fn render_frames(frames: usize) -> GraphicsState {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    for _ in 0..frames {
+        state.update(DT);
+    }
+    state.render(1.0);
+    state
+}
+
+#[test]
+fn first_frame() {
+    let state = render_frames(1);
+
+    assert_golden("square_first_frame", &state.framebuffer, Tolerance::EXACT);
+}
+
+#[test]
+fn after_half_a_second() {
+    let state = render_frames(30);
+
+    assert_golden("square_half_second", &state.framebuffer, Tolerance::EXACT);
+}
+
+#[test]
+fn after_bouncing_off_corner() {
+    let state = render_frames(60);
+
+    let velocity = state.square_velocity();
+    assert!(velocity.x < 0.0 && velocity.y < 0.0);
+    assert_golden("square_after_bounce", &state.framebuffer, Tolerance::EXACT);
+}
+
+#[test]
+fn render_interpolates_between_ticks() {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    state.update(DT);
+    state.render(0.0);
+    let previous = state.framebuffer.clone();
+
+    let mut expected = GraphicsState::new(WIDTH, HEIGHT);
+    expected.render(1.0);
+
+    assert_eq!(previous, expected.framebuffer);
+}
+
+#[test]
+fn sprite_replaces_solid_square() {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    let mut sprite = Image::new(4, 4);
+    sprite.pixels_mut().fill(Color::CYAN);
+    sprite.set_pixel(0, 0, Color::TRANSPARENT);
+    sprite.set_pixel(3, 3, Color::YELLOW.with_alpha(128));
+    let sprite = state.assets_mut().add(sprite);
+    state.square_sprite = Some(sprite);
+
+    state.render(1.0);
+
+    assert_golden("square_sprite", &state.framebuffer, Tolerance::EXACT);
+}
+
+#[test]
+fn spawned_entities_bounce_independently() {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    let entities: Vec<_> = (0..200)
+        .map(|i| {
+            let speed = 40.0 + i as f64;
+            state
+                .world
+                .spawn()
+                .with(Position { x: (i % 150) as f64, y: (i % 110) as f64 })
+                .with(PreviousPosition::default())
+                .with(Velocity { x: speed, y: -speed })
+                .with(Bounds { w: 4.0, h: 4.0 })
+                .with(Fill(Color::CYAN))
+                .id()
+        })
+        .collect();
+
+    for _ in 0..600 {
+        state.update(DT);
+    }
+
+    for entity in entities {
+        let pos = *state
+            .world
+            .get::<Position>(entity)
+            .unwrap();
+        assert!((0.0..=156.0).contains(&pos.x), "{entity:?} at {pos:?}");
+        assert!((0.0..=116.0).contains(&pos.y), "{entity:?} at {pos:?}");
+    }
+}
+
+#[test]
+fn colliding_squares_exchange_velocity() {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    let collider =
+        Collider(Shape::Aabb(Aabb::new(Vec2::ZERO, Vec2::new(10.0, 10.0))));
+    let mut spawn = |x: f64, vx: f64| {
+        state
+            .world
+            .spawn()
+            .with(Position { x, y: 50.0 })
+            .with(PreviousPosition::default())
+            .with(Velocity { x: vx, y: 0.0 })
+            .with(Bounds { w: 10.0, h: 10.0 })
+            .with(collider)
+            .id()
+    };
+    let left = spawn(40.0, 60.0);
+    let right = spawn(100.0, -30.0);
+
+    for _ in 0..60 {
+        state.update(DT);
+    }
+
+    let velocity = |e| *state.world.get::<Velocity>(e).unwrap();
+    let position = |e| *state.world.get::<Position>(e).unwrap();
+    assert_eq!(velocity(left).x, -30.0);
+    assert_eq!(velocity(right).x, 60.0);
+    assert!(position(right).x - position(left).x >= 10.0);
+}
+
+#[test]
+fn held_keys_move_the_square_every_tick() {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    state.teleport_square(72.0, 54.0);
+    state
+        .world
+        .insert(state.square, Velocity::default());
+    state
+        .input_mut()
+        .press(Button::Key(KeyCode::KeyD));
+
+    for _ in 0..6 {
+        state.update(DT);
+    }
+    let held = state.square_position();
+    state
+        .input_mut()
+        .release(Button::Key(KeyCode::KeyD));
+    state.update(DT);
+
+    assert!((held.x - 102.0).abs() < 1e-9, "moved to {held:?}");
+    assert_eq!(state.square_position(), held);
+}
+
+#[test]
+fn teleport_is_consumed_by_a_single_tick() {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    state
+        .world
+        .insert(state.square, Velocity::default());
+    let mut input = state.input_mut();
+    input.set_cursor(40.0, 30.0);
+    input.press(Button::Mouse(MouseButton::Left));
+    drop(input);
+
+    state.update(DT);
+    let teleported = state.square_position();
+    state.teleport_square(0.0, 0.0);
+    state.update(DT);
+
+    assert_eq!(teleported, Position { x: 32.0, y: 24.0 });
+    assert_eq!(state.square_position(), Position { x: 0.0, y: 0.0 });
+}
+
+#[test]
+fn resizing_keeps_the_arena() {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    let bounds = *state
+        .world
+        .get::<Bounds>(state.square)
+        .unwrap();
+
+    state.resize(320, 60);
+
+    assert_eq!(state.max_square_pos(), (144.0, 108.0));
+    assert_eq!(
+        *state
+            .world
+            .get::<Bounds>(state.square)
+            .unwrap(),
+        bounds
+    );
+    assert_eq!(state.camera().viewport, Vec2::new(320.0, 60.0));
+}
+
+#[test]
+fn clicks_map_through_the_camera() {
+    let mut state = GraphicsState::with_arena(WIDTH, HEIGHT, 1600, 1200);
+    state
+        .world
+        .insert(state.square, Velocity::default());
+    state.camera_mut().zoom = 0.1;
+    let mut input = state.input_mut();
+    input.set_cursor(120.0, 30.0);
+    input.press(Button::Mouse(MouseButton::Left));
+    drop(input);
+
+    state.update(DT);
+
+    // 40px right of and 30px above the center is (400, -300) world
+    // units from the arena's center at this zoom; the square is 160x120.
+    assert_eq!(state.square_position(), Position { x: 1120.0, y: 240.0 });
+    assert_eq!(state.max_square_pos(), (1440.0, 1080.0));
+}
+
+#[test]
+fn rotated_and_zoomed_camera() {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    let camera = state
+        .camera()
+        .with_position(Vec2::new(90.0, 60.0))
+        .with_zoom(2.0)
+        .with_rotation(0.3);
+    *state.camera_mut() = camera;
+
+    state.render(1.0);
+
+    assert_golden("camera_rotated", &state.framebuffer, Tolerance::EXACT);
+}
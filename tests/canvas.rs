@@ -0,0 +1,53 @@
+use winit::event::MouseButton;
+
+use window_app::canvas::{ScaleMode, VirtualCanvas};
+use window_app::components::{Position, Velocity};
+use window_app::framebuffer::Framebuffer;
+use window_app::golden::{Tolerance, assert_golden};
+use window_app::graphics::GraphicsState;
+use window_app::input::{Button, InputEvent};
+
+const CANVAS: VirtualCanvas = VirtualCanvas::new(160, 120, ScaleMode::Integer);
+const DT: f64 = 1.0 / 60.0;
+
+#[test]
+fn window_resizes_leave_the_canvas_alone() {
+    let mut state = GraphicsState::with_canvas(CANVAS, 800, 600);
+
+    state.apply_input(&InputEvent::Resized { width: 1000, height: 300 });
+
+    assert_eq!(state.window_size(), (1000, 300));
+    assert_eq!(
+        (state.framebuffer.width(), state.framebuffer.height()),
+        (160, 120)
+    );
+    assert_eq!(state.max_square_pos(), (144.0, 108.0));
+}
+
+#[test]
+fn clicks_map_through_the_letterbox() {
+    // Scaled 2x into 320x240, centered with 90px bars left and right.
+    let mut state = GraphicsState::with_canvas(CANVAS, 500, 240);
+    state
+        .world
+        .insert(state.square, Velocity::default());
+
+    state.apply_input(&InputEvent::CursorMoved { x: 170.0, y: 60.0 });
+    state.apply_input(&InputEvent::Press(Button::Mouse(MouseButton::Left)));
+    state.update(DT);
+
+    assert_eq!(state.input().cursor(), (40.0, 30.0));
+    assert_eq!(state.square_position(), Position { x: 32.0, y: 24.0 });
+}
+
+#[test]
+fn letterboxed_first_frame() {
+    let mut state = GraphicsState::with_canvas(CANVAS, 400, 300);
+    state.update(DT);
+    state.render(1.0);
+    let mut window = Framebuffer::new(400, 300);
+
+    CANVAS.present(&state.framebuffer, &mut window);
+
+    assert_golden("canvas_letterboxed", &window, Tolerance::EXACT);
+}
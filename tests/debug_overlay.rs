@@ -0,0 +1,101 @@
+use window_app::color::Color;
+use window_app::debug_overlay::{DebugInfo, DebugOverlay};
+use window_app::framebuffer::Framebuffer;
+use window_app::golden::{Tolerance, assert_golden};
+use window_app::logging::{Level, Record};
+
+#[test]
+fn overlay_shows_stats_and_graph() {
+    let mut overlay = DebugOverlay::new();
+    overlay.toggle();
+    for i in 0..90 {
+        let spike = if i % 30 == 29 { 0.025 } else { 0.0 };
+        overlay.stats.record(1.0 / 64.0 + spike);
+    }
+    let info = DebugInfo {
+        window_size: (320, 160),
+        scale_factor: 1.5,
+        square_position: (12.5, 40.0),
+        square_velocity: (100.0, -100.0),
+    };
+    let mut fb = Framebuffer::new(320, 160);
+    fb.fill(Color::rgb(0x20, 0x20, 0x20));
+
+    overlay.draw(&mut fb, &info);
+
+    assert_golden("debug_overlay", &fb, Tolerance::EXACT);
+}
+
+#[test]
+fn hidden_overlay_draws_nothing() {
+    let overlay = DebugOverlay::new();
+    let mut fb = Framebuffer::new(64, 64);
+
+    overlay.draw(&mut fb, &DebugInfo::default());
+
+    assert_eq!(fb, Framebuffer::new(64, 64));
+}
+
+#[test]
+fn overlay_doubles_on_a_2x_display() {
+    let mut overlay = DebugOverlay::new();
+    overlay.toggle();
+    for i in 0..60 {
+        overlay
+            .stats
+            .record(if i % 20 == 19 { 0.04 } else { 1.0 / 60.0 });
+    }
+    let info = DebugInfo {
+        window_size: (640, 320),
+        scale_factor: 2.0,
+        square_position: (12.5, 40.0),
+        square_velocity: (100.0, -100.0),
+    };
+    let mut fb = Framebuffer::new(640, 320);
+    fb.fill(Color::rgb(0x20, 0x20, 0x20));
+
+    overlay.draw(&mut fb, &info);
+
+    assert_golden("debug_overlay_2x", &fb, Tolerance::EXACT);
+}
+
+#[test]
+fn overlay_lists_recent_log_records() {
+    let mut overlay = DebugOverlay::new();
+    overlay.toggle();
+    let record = |frame, level, message: &str| Record {
+        frame,
+        level,
+        module: "window_app",
+        message: message.into(),
+    };
+    let records = [
+        record(3, Level::Info, "Saved input to input.ron"),
+        record(57, Level::Warn, "Using built-in bindings: file not found"),
+        record(120, Level::Error, "window surface failed"),
+    ];
+    let mut fb = Framebuffer::new(320, 160);
+    fb.fill(Color::rgb(0x20, 0x20, 0x20));
+
+    overlay.draw_log(&mut fb, &records, 1.0);
+
+    assert_golden("debug_overlay_log", &fb, Tolerance::EXACT);
+}
+
+#[test]
+fn log_skips_frames_too_narrow_for_text() {
+    let mut overlay = DebugOverlay::new();
+    overlay.toggle();
+    let records = [Record {
+        frame: 1,
+        level: Level::Warn,
+        module: "window_app",
+        message: "minimized".into(),
+    }];
+
+    for width in [1, 16] {
+        let mut fb = Framebuffer::new(width, 64);
+        overlay.draw_log(&mut fb, &records, 1.0);
+        assert_eq!(fb, Framebuffer::new(width, 64));
+    }
+}
@@ -0,0 +1,56 @@
+use winit::event::MouseButton;
+
+use window_app::color::Color;
+use window_app::components::{Position, Velocity};
+use window_app::graphics::GraphicsState;
+use window_app::input::{Button, InputEvent};
+
+const DT: f64 = 1.0 / 60.0;
+
+fn magenta_pixels(state: &mut GraphicsState) -> usize {
+    state.render(1.0);
+    let magenta = Color::MAGENTA.to_0rgb();
+    state
+        .framebuffer
+        .pixels()
+        .iter()
+        .filter(|&&pixel| pixel == magenta)
+        .count()
+}
+
+#[test]
+fn world_is_sized_in_logical_pixels() {
+    let mut low = GraphicsState::for_window(160, 120, 1.0);
+    let mut high = GraphicsState::for_window(320, 240, 2.0);
+
+    assert_eq!(high.max_square_pos(), low.max_square_pos());
+    assert_eq!(high.square_position(), low.square_position());
+    assert_eq!(magenta_pixels(&mut high), 4 * magenta_pixels(&mut low));
+}
+
+#[test]
+fn physical_clicks_land_on_the_same_world_point() {
+    let mut state = GraphicsState::for_window(320, 240, 2.0);
+    state
+        .world
+        .insert(state.square, Velocity::default());
+
+    state.apply_input(&InputEvent::CursorMoved { x: 80.0, y: 60.0 });
+    state.apply_input(&InputEvent::Press(Button::Mouse(MouseButton::Left)));
+    state.update(DT);
+
+    assert_eq!(state.square_position(), Position { x: 32.0, y: 24.0 });
+}
+
+#[test]
+fn moving_to_another_display_keeps_the_view() {
+    let mut state = GraphicsState::for_window(160, 120, 1.0);
+    let before = magenta_pixels(&mut state);
+
+    state.apply_input(&InputEvent::ScaleFactorChanged { scale_factor: 2.0 });
+    state.apply_input(&InputEvent::Resized { width: 320, height: 240 });
+
+    assert_eq!(state.scale_factor(), 2.0);
+    assert_eq!(state.window_metrics().logical_size(), (160.0, 120.0));
+    assert_eq!(magenta_pixels(&mut state), 4 * before);
+}
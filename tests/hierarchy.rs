@@ -0,0 +1,91 @@
+use std::f64::consts::FRAC_PI_4;
+
+use window_app::collision::{Aabb, Circle, Obb, Shape};
+use window_app::color::Color;
+use window_app::components::{
+    Drawable, Fill, GlobalTransform, Label, LocalTransform, Parent, Position,
+};
+use window_app::golden::{Tolerance, assert_golden};
+use window_app::graphics::GraphicsState;
+use window_app::math::Vec2;
+use window_app::transform::Transform2D;
+
+const DT: f64 = 1.0 / 60.0;
+
+fn local(x: f64, y: f64) -> Transform2D {
+    Transform2D::from_translation(Vec2::new(x, y))
+}
+
+#[test]
+fn label_follows_the_square() {
+    let mut state = GraphicsState::new(160, 120);
+    let label = state
+        .world
+        .spawn()
+        .with(Parent(state.square))
+        .with(LocalTransform(local(0.0, -10.0)))
+        .with(Label { text: "you".into(), color: Color::WHITE })
+        .id();
+
+    for _ in 0..30 {
+        state.update(DT);
+    }
+
+    let square = state.square_position();
+    let global = state
+        .world
+        .get::<GlobalTransform>(label)
+        .map(|global| global.0.translation);
+    assert_eq!(global, Some(Vec2::new(square.x, square.y - 10.0)));
+}
+
+#[test]
+fn turret_rides_on_a_tank() {
+    let mut state = GraphicsState::new(160, 120);
+    state
+        .world
+        .insert(state.square, Position { x: 20.0, y: 80.0 });
+    let tank = state
+        .world
+        .spawn()
+        .with(LocalTransform(
+            local(80.0, 60.0)
+                .with_rotation(-FRAC_PI_4)
+                .with_scale(Vec2::new(1.5, 1.5)),
+        ))
+        .with(Drawable(Shape::Aabb(Aabb::new(
+            Vec2::new(-20.0, -12.0),
+            Vec2::new(20.0, 12.0),
+        ))))
+        .with(Fill(Color::GREEN))
+        .id();
+    let turret = state
+        .world
+        .spawn()
+        .with(Parent(tank))
+        .with(LocalTransform(local(4.0, 0.0)))
+        .with(Drawable(Shape::Circle(Circle::new(Vec2::ZERO, 7.0))))
+        .with(Fill(Color::YELLOW))
+        .id();
+    state
+        .world
+        .spawn()
+        .with(Parent(turret))
+        .with(LocalTransform(local(0.0, 0.0).with_rotation(FRAC_PI_4)))
+        .with(Drawable(Shape::Obb(Obb::new(
+            Vec2::new(14.0, 0.0),
+            Vec2::new(10.0, 2.0),
+            0.0,
+        ))))
+        .with(Fill(Color::RED));
+    state
+        .world
+        .spawn()
+        .with(Parent(state.square))
+        .with(LocalTransform(local(-2.0, -12.0)))
+        .with(Label { text: "P1".into(), color: Color::WHITE });
+
+    state.render(1.0);
+
+    assert_golden("hierarchy", &state.framebuffer, Tolerance::EXACT);
+}
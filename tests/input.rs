@@ -0,0 +1,8 @@
+use window_app::input::InputMap;
+
+#[test]
+fn shipped_bindings_match_builtin() {
+    let map = InputMap::load("assets/bindings.ron").unwrap();
+
+    assert_eq!(map, InputMap::builtin());
+}
@@ -0,0 +1,69 @@
+use window_app::collision::{Aabb, Shape};
+use window_app::color::Color;
+use window_app::components::{Collider, Position, RigidBody, Velocity};
+use window_app::framebuffer::Framebuffer;
+use window_app::golden::{Tolerance, assert_golden};
+use window_app::graphics::GraphicsState;
+use window_app::inspector::{Inspector, describe};
+use window_app::math::Vec2;
+
+fn scene() -> GraphicsState {
+    let mut state = GraphicsState::new(160, 120);
+    state
+        .world
+        .spawn()
+        .with(Position { x: 80.0, y: 110.0 })
+        .with(Collider(Shape::Aabb(Aabb::new(
+            Vec2::new(-80.0, -10.0),
+            Vec2::new(80.0, 10.0),
+        ))))
+        .with(RigidBody::fixed());
+    state
+}
+
+#[test]
+fn lists_every_entity() {
+    let state = scene();
+
+    assert_eq!(
+        describe(&state.world),
+        "2 entities\n\
+         #0   pos 72.0, 54.0 vel 100.0, 100.0 player\n\
+         #1   pos 80.0, 110.0 collider fixed\n"
+    );
+}
+
+#[test]
+fn tracks_the_world_as_it_changes() {
+    let mut state = scene();
+    state
+        .world
+        .insert(state.square, Velocity { x: 60.0, y: 0.0 });
+    state.update(1.0 / 60.0);
+
+    assert!(
+        describe(&state.world).contains("#0   pos 73.0, 54.0 vel 60.0, 0.0")
+    );
+}
+
+#[test]
+fn draws_the_listing() {
+    let state = scene();
+    let mut fb = Framebuffer::new(320, 80);
+
+    Inspector::new().draw(&state.world, &mut fb, 1.0);
+
+    assert_golden("inspector", &fb, Tolerance::EXACT);
+}
+
+#[test]
+fn narrow_windows_only_clear() {
+    let state = scene();
+    let mut fb = Framebuffer::new(12, 40);
+
+    Inspector::new().draw(&state.world, &mut fb, 1.0);
+
+    let mut cleared = Framebuffer::new(12, 40);
+    cleared.fill(Color::rgb(0x18, 0x18, 0x28));
+    assert_eq!(fb, cleared);
+}
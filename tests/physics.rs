@@ -0,0 +1,71 @@
+use window_app::collision::{Aabb, Circle, Shape};
+use window_app::color::Color;
+use window_app::components::{
+    AngularVelocity, Collider, Fill, Position, PreviousPosition, RigidBody,
+    Rotation, Velocity,
+};
+use window_app::golden::{Tolerance, assert_golden};
+use window_app::graphics::GraphicsState;
+use window_app::math::Vec2;
+
+const WIDTH: usize = 160;
+const HEIGHT: usize = 120;
+const DT: f64 = 1.0 / 60.0;
+
+fn spawn_body(
+    state: &mut GraphicsState,
+    x: f64,
+    y: f64,
+    shape: Shape,
+    color: Color,
+) {
+    state
+        .world
+        .spawn()
+        .with(Position { x, y })
+        .with(PreviousPosition { x, y })
+        .with(Velocity::default())
+        .with(Rotation(0.1 * x.sin()))
+        .with(AngularVelocity::default())
+        .with(Collider(shape))
+        .with(RigidBody::dynamic(1.0).with_restitution(0.2))
+        .with(Fill(color));
+}
+
+#[test]
+fn boxes_and_balls_pile_up() {
+    let mut state = GraphicsState::new(WIDTH, HEIGHT);
+    state.world.despawn(state.square);
+    state
+        .world
+        .spawn()
+        .with(Position { x: 80.0, y: 115.0 })
+        .with(Collider(Shape::Aabb(Aabb::from_center(
+            Vec2::ZERO,
+            Vec2::new(80.0, 5.0),
+        ))))
+        .with(RigidBody::fixed())
+        .with(Fill(Color::rgb(0x80, 0x80, 0x80)));
+
+    let square =
+        Shape::Aabb(Aabb::from_center(Vec2::ZERO, Vec2::new(6.0, 6.0)));
+    let ball = Shape::Circle(Circle::new(Vec2::ZERO, 5.0));
+    for row in 0..3 {
+        for column in 0..4 {
+            let x = 50.0 + column as f64 * 18.0 + row as f64 * 4.0;
+            let y = 20.0 + row as f64 * 25.0;
+            if (row + column) % 2 == 0 {
+                spawn_body(&mut state, x, y, square, Color::CYAN);
+            } else {
+                spawn_body(&mut state, x, y, ball, Color::YELLOW);
+            }
+        }
+    }
+
+    for _ in 0..180 {
+        state.update(DT);
+    }
+    state.render(1.0);
+
+    assert_golden("physics_pile", &state.framebuffer, Tolerance::EXACT);
+}
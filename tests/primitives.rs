@@ -0,0 +1,66 @@
+use window_app::color::{BlendMode, Color};
+use window_app::framebuffer::Framebuffer;
+use window_app::golden::{Tolerance, assert_golden};
+
+#[test]
+fn primitive_showcase() {
+    let mut fb = Framebuffer::new(128, 96);
+    fb.fill(Color::rgb(0x20, 0x20, 0x20));
+
+    fb.fill_rect(4, 4, 24, 16, Color::rgb(0xFF, 0x00, 0xFF));
+    fb.draw_rect(32, 4, 24, 16, Color::rgb(0x00, 0xFF, 0x00));
+    fb.fill_circle(76, 12, 9, Color::rgb(0x00, 0x80, 0xFF));
+    fb.draw_circle(104, 12, 9, Color::rgb(0xFF, 0xFF, 0x00));
+
+    fb.fill_ellipse(20, 40, 16, 8, Color::rgb(0xFF, 0x80, 0x00));
+    fb.draw_ellipse(60, 40, 18, 10, Color::rgb(0x00, 0xFF, 0xFF));
+    fb.fill_triangle(
+        (88.0, 52.0),
+        (124.0, 52.0),
+        (106.0, 28.0),
+        Color::rgb(0xFF, 0xFF, 0xFF),
+    );
+
+    fb.draw_line(0, 95, 127, 60, Color::rgb(0xFF, 0x00, 0x00));
+    fb.draw_line_f(-20.0, 60.0, 40.0, 95.5, Color::rgb(0x00, 0xFF, 0x00));
+    fb.draw_thick_line(
+        50.0,
+        90.0,
+        80.0,
+        66.0,
+        5.0,
+        Color::rgb(0xC0, 0xC0, 0xC0),
+    );
+    fb.fill_polygon(
+        &[(90.0, 94.0), (100.0, 62.0), (110.0, 94.0), (100.0, 80.0)],
+        Color::rgb(0xFF, 0x00, 0xFF),
+    );
+    fb.draw_polygon(
+        &[(112.0, 62.0), (126.0, 70.0), (116.0, 92.0)],
+        Color::rgb(0xFF, 0xFF, 0x00),
+    );
+
+    assert_golden("primitive_showcase", &fb, Tolerance::EXACT);
+}
+
+#[test]
+fn translucent_blend_modes() {
+    let mut fb = Framebuffer::new(96, 48);
+    fb.fill(Color::rgb(0x20, 0x20, 0x20));
+    fb.fill_rect(0, 24, 96, 24, Color::rgb(0x80, 0x80, 0xC0));
+
+    let modes = [
+        BlendMode::SourceOver,
+        BlendMode::Additive,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+    ];
+    for (i, mode) in modes.into_iter().enumerate() {
+        fb.set_blend_mode(mode);
+        let x = 12 + i as i32 * 24;
+        fb.fill_circle(x, 18, 10, Color::rgba(0xFF, 0x40, 0x00, 0xC0));
+        fb.fill_circle(x, 30, 10, Color::rgba(0x00, 0x80, 0xFF, 0x80));
+    }
+
+    assert_golden("translucent_blend_modes", &fb, Tolerance::EXACT);
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use window_app::components::{Name, Parent, Sprite};
+use window_app::golden::{Tolerance, assert_golden};
+use window_app::graphics::GraphicsState;
+use window_app::scene::{Scene, SceneFormat};
+
+const DEMO_SCENE: &str = "assets/scenes/demo.ron";
+const DT: f64 = 1.0 / 60.0;
+
+fn demo_state() -> GraphicsState {
+    let mut state = GraphicsState::new(320, 180);
+    state
+        .load_scene(&Scene::load(DEMO_SCENE).unwrap(), "assets/scenes")
+        .unwrap();
+    state
+}
+
+#[test]
+fn demo_scene_replaces_the_square() {
+    let state = demo_state();
+
+    assert_eq!(state.world.len(), 8);
+    let name = state
+        .world
+        .get::<Name>(state.square)
+        .map(|name| name.0.clone());
+    assert_eq!(name.as_deref(), Some("player"));
+    let sprite = state
+        .world
+        .get::<Sprite>(state.square)
+        .unwrap();
+    assert_eq!(sprite.path, Path::new("../square.png"));
+}
+
+#[test]
+fn demo_scene_renders() {
+    let mut state = demo_state();
+    for _ in 0..30 {
+        state.update(DT);
+    }
+    state.render(1.0);
+
+    assert_golden("scene_demo", &state.framebuffer, Tolerance::EXACT);
+}
+
+#[test]
+fn captured_scenes_survive_json() {
+    let state = demo_state();
+    let captured = Scene::capture(&state.world);
+
+    let json = captured
+        .to_string(SceneFormat::Json)
+        .unwrap();
+    let reloaded = Scene::parse(&json, SceneFormat::Json).unwrap();
+    assert_eq!(reloaded, captured);
+
+    let mut copy = GraphicsState::new(320, 180);
+    copy.load_scene(&reloaded, "assets/scenes")
+        .unwrap();
+    let parents = |state: &GraphicsState| {
+        let mut count = 0;
+        state
+            .world
+            .query::<&Parent>(|_, _| count += 1);
+        count
+    };
+    assert_eq!(parents(&copy), 3);
+    assert_eq!(Scene::capture(&copy.world), captured);
+}
+
+#[test]
+fn failed_loads_keep_the_current_scene() {
+    let mut state = GraphicsState::new(320, 180);
+    let scene = Scene::parse(
+        r#"(version: 1, entities: [(sprite: "missing.png")])"#,
+        SceneFormat::Ron,
+    )
+    .unwrap();
+
+    let err = state
+        .load_scene(&scene, "assets")
+        .unwrap_err();
+
+    assert!(
+        err.to_string()
+            .starts_with("sprite missing.png: "),
+        "{err}"
+    );
+    assert_eq!(state.world.len(), 1);
+    assert!(state.world.is_alive(state.square));
+}
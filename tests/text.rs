@@ -0,0 +1,108 @@
+use std::cell::RefCell;
+
+use window_app::color::Color;
+use window_app::font::{BitmapFont, GlyphCache, TrueTypeFont};
+use window_app::framebuffer::Framebuffer;
+use window_app::golden::{Tolerance, assert_golden};
+use window_app::text::{Align, Font, TextStyle, measure_text};
+
+/// Cantarell, under the SIL Open Font License (tests/fonts/OFL.txt).
+const FONT_PATH: &str = "tests/fonts/Cantarell-Regular.ttf";
+
+#[test]
+fn builtin_font_showcase() {
+    let small = BitmapFont::builtin_8x8();
+    let tall = BitmapFont::builtin_8x16();
+    let mut fb = Framebuffer::new(160, 120);
+    fb.fill(Color::rgb(0x20, 0x20, 0x20));
+
+    fb.draw_text(&small, "Score: 1234", 4, 4, &TextStyle::default());
+    fb.draw_text(
+        &tall,
+        "Hello!",
+        80,
+        16,
+        &TextStyle {
+            color: Color::YELLOW,
+            align: Align::Center,
+            ..TextStyle::default()
+        },
+    );
+    fb.draw_text(
+        &small,
+        "x2",
+        156,
+        36,
+        &TextStyle {
+            color: Color::CYAN,
+            scale: 2,
+            align: Align::Right,
+            ..TextStyle::default()
+        },
+    );
+    fb.draw_text(
+        &small,
+        "The quick brown fox jumps over the lazy dog.",
+        4,
+        60,
+        &TextStyle {
+            color: Color::MAGENTA.with_alpha(192),
+            max_width: Some(152),
+            align: Align::Center,
+            ..TextStyle::default()
+        },
+    );
+
+    assert_golden("builtin_font_showcase", &fb, Tolerance::EXACT);
+}
+
+#[test]
+fn truetype_font_renders_through_glyph_cache() {
+    let font = TrueTypeFont::load(FONT_PATH).unwrap();
+    let cache = RefCell::new(GlyphCache::new(256, 256));
+    let sized = font.sized(20.0, &cache);
+    let mut fb = Framebuffer::new(160, 60);
+
+    fb.draw_text(&sized, "AVA", 4, 4, &TextStyle::default());
+    let cached = cache.borrow().len();
+    fb.draw_text(&sized, "VA", 4, 30, &TextStyle::default());
+
+    assert_eq!(cached, 2, "A and V rasterized once each");
+    assert_eq!(cache.borrow().len(), cached);
+    assert!(
+        fb.pixels()
+            .iter()
+            .any(|&p| p != 0 && p != 0x00FFFFFF)
+    );
+
+    let (plain, _) = measure_text(&sized, "AV", &TextStyle::default());
+    let unkerned = sized.advance('A', 1) + sized.advance('V', 1);
+    assert!(plain <= unkerned);
+
+    let (scaled, height) = measure_text(
+        &sized,
+        "AV",
+        &TextStyle { scale: 2, ..TextStyle::default() },
+    );
+    assert!(scaled > plain && height == sized.line_height(2));
+}
+
+#[test]
+fn oversized_glyphs_draw_without_evicting_the_cache() {
+    let font = TrueTypeFont::load(FONT_PATH).unwrap();
+    let cache = RefCell::new(GlyphCache::new(32, 32));
+    let mut fb = Framebuffer::new(64, 64);
+    fb.draw_text(&font.sized(16.0, &cache), "o", 0, 0, &TextStyle::default());
+
+    let mut big = Framebuffer::new(256, 256);
+    let huge = font.sized(200.0, &cache);
+    big.draw_text(&huge, "W", 0, 0, &TextStyle::default());
+
+    let lit = big
+        .pixels()
+        .iter()
+        .filter(|&&p| p != 0)
+        .count();
+    assert!(lit > 32 * 32, "W covers more than the whole atlas");
+    assert_eq!(cache.borrow().len(), 1, "only o is cached");
+}